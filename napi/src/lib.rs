@@ -25,7 +25,26 @@ fn transform_modules(ctx: CallContext) -> Result<JsObject> {
 			// Spawn the CPU-intensive work onto a separate thread in the thread pool
 			let result = task::spawn_blocking(move || js_lib_interface::transform_modules(config))
 				.await
-				.unwrap()
+				.map_err(|e| napi::Error::from_reason(e.to_string()))?
+				.map_err(|e| napi::Error::from_reason(e.to_string()))?;
+
+			Ok(result)
+		},
+		|env, result| env.to_js_value(&result),
+	)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+#[js_function(1)]
+fn check_modules(ctx: CallContext) -> Result<JsObject> {
+	let opts = ctx.get::<JsObject>(0)?;
+	let config: js_lib_interface::TransformModulesOptions = ctx.env.from_js_value(opts)?;
+
+	ctx.env.execute_tokio_future(
+		async move {
+			let result = task::spawn_blocking(move || js_lib_interface::check_modules(config))
+				.await
+				.map_err(|e| napi::Error::from_reason(e.to_string()))?
 				.map_err(|e| napi::Error::from_reason(e.to_string()))?;
 
 			Ok(result)
@@ -37,6 +56,7 @@ fn transform_modules(ctx: CallContext) -> Result<JsObject> {
 #[module_exports]
 fn init(mut exports: JsObject) -> Result<()> {
 	exports.create_named_method("transform_modules", transform_modules)?;
+	exports.create_named_method("check_modules", check_modules)?;
 
 	Ok(())
 }
@@ -1,13 +1,14 @@
 use crate::component::*;
+use crate::processing_failure::ProcessingFailure;
 use crate::segment::Segment;
 use crate::transform::TransformOptions;
 use crate::{component::Language, import_clean_up::ImportCleanUp};
-use oxc_allocator::{Allocator, Box as OxcBox, CloneIn, IntoIn, Vec as OxcVec};
+use oxc_allocator::{Allocator, Box as OxcBox, IntoIn, Vec as OxcVec};
 use oxc_ast::ast::*;
 use oxc_ast::*;
 use oxc_codegen::{Codegen, CodegenOptions};
 use oxc_minifier::*;
-use oxc_span::{SourceType, SPAN};
+use oxc_span::{GetSpan, SourceType, Span, SPAN};
 use serde::Serialize;
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
@@ -16,14 +17,18 @@ pub struct QrlComponent {
     pub language: Language,
     pub code: String,
     pub qrl: Qrl,
+    /// Location of the extracted expression in the original source.
+    #[serde(skip)]
+    pub span: Span,
 }
 
 impl QrlComponent {
-    pub(crate) fn new(
+    pub(crate) fn new<'a>(
         options: &TransformOptions,
         source_info: &SourceInfo,
         id: Id,
-        exported_expression: Expression<'_>,
+        exported_expression: Expression<'a>,
+        hoisted: Vec<Statement<'a>>,
         imports: Vec<Import>,
         qrl_type: QrlType,
     ) -> QrlComponent {
@@ -31,31 +36,39 @@ impl QrlComponent {
         let qrl = Qrl::new(&id.local_file_name, &id.symbol_name, qrl_type);
 
         let source_type: SourceType = language.into();
+        let span = exported_expression.span();
 
-        let code = Self::gen(
-            options,
-            &id,
-            exported_expression,
-            imports,
-            &source_type,
-            source_info,
-            &Allocator::default(),
-        );
+        let code = if options.check_only {
+            String::new()
+        } else {
+            Self::gen(
+                options,
+                &id,
+                exported_expression,
+                hoisted,
+                imports,
+                &source_type,
+                &Allocator::default(),
+            )
+        };
         QrlComponent {
             id,
             language: source_info.language.clone(),
             code,
             qrl,
+            span,
         }
     }
 
-    fn gen(
+    /// Generates the segment module: its imports, the declarations hoisted out of the segment
+    /// and finally the exported expression itself.
+    fn gen<'a>(
         options: &TransformOptions,
         id: &Id,
-        exported_expression: Expression<'_>,
+        exported_expression: Expression<'a>,
+        hoisted: Vec<Statement<'a>>,
         imports: Vec<Import>,
         source_type: &SourceType,
-        _source_info: &SourceInfo,
         allocator: &Allocator,
     ) -> String {
         let name = &id.symbol_name;
@@ -106,6 +119,7 @@ impl QrlComponent {
 
         let mut body = ast_builder.vec_from_iter(imports);
 
+        body.extend(hoisted);
         body.push(export);
 
         let ast_builder = AstBuilder::new(allocator);
@@ -148,37 +162,34 @@ impl QrlComponent {
         }
     }
 
-    /// Create a QrlComponent from an `Expression`.
-    pub(crate) fn from_expression(
-        expr: Expression<'_>,
+    /// Create a QrlComponent from an `Expression`, which has to be the argument of a `$` marker
+    /// call that is the last of `segments`.
+    pub(crate) fn from_expression<'a>(
+        expr: Expression<'a>,
+        hoisted: Vec<Statement<'a>>,
         imports: Vec<Import>,
         segments: &Vec<Segment>,
         scope: &Option<String>,
         options: &TransformOptions,
         source_info: &SourceInfo,
-    ) -> QrlComponent {
-        let qrl_type: QrlType = segments
-            .last()
-            .iter()
-            .flat_map(|segment| segment.qrl_type())
-            .last()
-            .unwrap(); // TODO Clean this up.
+    ) -> Result<QrlComponent, ProcessingFailure> {
+        let Some(qrl_type) = segments.last().and_then(|segment| segment.qrl_type()) else {
+            return Err(ProcessingFailure::Internal(
+                "QRL extracted outside of a marker call".to_string(),
+                expr.span(),
+            ));
+        };
 
         let id = Id::new(source_info, segments, &options.target, scope);
 
-        QrlComponent::new(options, source_info, id, expr, imports, qrl_type)
-    }
-
-    pub(crate) fn from_call_expression_argument(
-        arg: &Argument,
-        imports: Vec<Import>,
-        segments: &Vec<Segment>,
-        scope: &Option<String>,
-        options: &TransformOptions,
-        source_info: &SourceInfo,
-        allocator: &Allocator,
-    ) -> QrlComponent {
-        let init = arg.clone_in(allocator).into_expression();
-        Self::from_expression(init, imports, segments, scope, options, source_info)
+        Ok(QrlComponent::new(
+            options,
+            source_info,
+            id,
+            expr,
+            hoisted,
+            imports,
+            qrl_type,
+        ))
     }
 }
@@ -1,6 +1,8 @@
-use crate::component::{Import, QRL, QRL_SUFFIX, QWIK_CORE_SOURCE};
+use crate::component::{
+    Import, NOOP_QRL, NOOP_QRL_DEV, QRL, QRL_DEV, QRL_SUFFIX, QWIK_CORE_SOURCE,
+};
 use crate::ext::AstBuilderExt;
-use oxc_allocator::{Allocator, Box as OxcBox, CloneIn, FromIn, Vec as OxcVec};
+use oxc_allocator::{Allocator, Box as OxcBox, FromIn, Vec as OxcVec};
 use oxc_ast::ast::*;
 use oxc_ast::AstBuilder;
 use oxc_semantic::{NodeId, ReferenceFlags, ReferenceId, ScopeId, SymbolFlags, SymbolId};
@@ -15,6 +17,29 @@ pub enum QrlType {
     Qrl,
     PrefixedQrl(String),
     IndexedQrl(usize),
+    /// A segment that is stripped from this build. Its call site keeps its shape, including the
+    /// captured bindings, but points at nothing: `_noopQrl("symbol", [a, b])`. Holds the prefix of
+    /// the marker the QRL is passed to, as [QrlType::PrefixedQrl] does, e.g. `serverQrl(_noopQrl(..))`.
+    NoopQrl(Option<String>),
+}
+
+impl QrlType {
+    /// The type of the QRL that replaces a stripped segment of this type.
+    pub fn into_noop(self) -> QrlType {
+        match self {
+            QrlType::PrefixedQrl(prefix) => QrlType::NoopQrl(Some(prefix)),
+            QrlType::Qrl | QrlType::IndexedQrl(_) => QrlType::NoopQrl(None),
+            noop @ QrlType::NoopQrl(_) => noop,
+        }
+    }
+
+    /// The prefix of the `<prefix>Qrl` function the QRL is passed to, if any.
+    fn wrapper_prefix(&self) -> Option<&str> {
+        match self {
+            QrlType::PrefixedQrl(prefix) | QrlType::NoopQrl(Some(prefix)) => Some(prefix),
+            QrlType::Qrl | QrlType::IndexedQrl(_) | QrlType::NoopQrl(None) => None,
+        }
+    }
 }
 
 impl From<QrlType> for Import {
@@ -22,6 +47,14 @@ impl From<QrlType> for Import {
         match value {
             QrlType::Qrl => Import::qrl(),
             QrlType::IndexedQrl(_) => Import::qrl(),
+            QrlType::NoopQrl(None) => Import::new(vec![NOOP_QRL.into()], QWIK_CORE_SOURCE),
+            QrlType::NoopQrl(Some(prefix)) => Import::new(
+                vec![
+                    format!("{}{}", prefix, QRL_SUFFIX).as_str().into(),
+                    NOOP_QRL.into(),
+                ],
+                QWIK_CORE_SOURCE,
+            ),
             QrlType::PrefixedQrl(prefix) => Import::new(
                 vec![
                     format!("{}{}", prefix, QRL_SUFFIX).as_str().into(),
@@ -33,19 +66,120 @@ impl From<QrlType> for Import {
     }
 }
 
+/// Where the expression behind a QRL was written, which dev builds pass to the runtime so that
+/// errors and the devtools can point back to the source.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct QrlDev {
+    pub file: String,
+    /// Byte offsets of the start and end of the expression.
+    pub lo: u32,
+    pub hi: u32,
+    pub display_name: String,
+}
+
+impl QrlDev {
+    /// Creates the trailing argument of a dev mode QRL call.
+    ///
+    /// # Examples
+    /// ```javascript
+    /// { file: "/src/app.tsx", lo: 88, hi: 200, displayName: "app.tsx_App_component" }
+    /// ```
+    pub fn to_expression<'a>(&self, ast_builder: &AstBuilder<'a>) -> Expression<'a> {
+        let property = |name: &'static str, value: Expression<'a>| {
+            ast_builder.object_property_kind_object_property(
+                SPAN,
+                PropertyKind::Init,
+                ast_builder.property_key_static_identifier(SPAN, name),
+                value,
+                false,
+                false,
+                false,
+            )
+        };
+        let string = |value: &str| {
+            ast_builder.expression_string_literal(SPAN, ast_builder.atom(value), None)
+        };
+        let number = |value: u32| {
+            ast_builder.expression_numeric_literal(SPAN, value.into(), None, NumberBase::Decimal)
+        };
+        ast_builder.expression_object(
+            SPAN,
+            ast_builder.vec_from_array([
+                property("file", string(&self.file)),
+                property("lo", number(self.lo)),
+                property("hi", number(self.hi)),
+                property("displayName", string(&self.display_name)),
+            ]),
+        )
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 pub struct Qrl {
     pub rel_path: PathBuf,
     pub display_name: String,
     pub qrl_type: QrlType,
+    /// The specifier that is lazily imported to resolve this QRL.
+    pub import_path: String,
+    /// Set in dev mode, which creates the QRL with `qrlDEV` instead of `qrl`.
+    pub dev: Option<QrlDev>,
 }
 
 impl Qrl {
     pub fn new<T: Into<PathBuf>>(rel_path: T, display_name: &str, qrl_type: QrlType) -> Self {
+        let rel_path = rel_path.into();
+        let import_path = format!("./{}.js", rel_path.file_name().unwrap().to_string_lossy());
         Self {
-            rel_path: rel_path.into(),
+            rel_path,
             display_name: display_name.into(),
             qrl_type,
+            import_path,
+            dev: None,
+        }
+    }
+
+    pub fn with_dev(self, dev: QrlDev) -> Self {
+        Self {
+            dev: Some(dev),
+            ..self
+        }
+    }
+
+    /// The name of the function that creates the QRL: `qrl` or `_noopQrl`, suffixed with `DEV` in
+    /// dev mode.
+    fn callee_name(&self) -> &'static str {
+        match (&self.qrl_type, self.dev.is_some()) {
+            (QrlType::NoopQrl(_), false) => NOOP_QRL,
+            (QrlType::NoopQrl(_), true) => NOOP_QRL_DEV,
+            (_, false) => QRL,
+            (_, true) => QRL_DEV,
+        }
+    }
+
+    /// The imports needed by the call expression created for this QRL.
+    pub fn import(&self) -> Import {
+        let callee = self.callee_name().into();
+        let names = match self.qrl_type.wrapper_prefix() {
+            None => vec![callee],
+            Some(prefix) => vec![format!("{}{}", prefix, QRL_SUFFIX).as_str().into(), callee],
+        };
+        Import::new(names, QWIK_CORE_SOURCE)
+    }
+
+    /// Creates a QRL that points at an existing export of another module, rather than at a newly
+    /// extracted segment.
+    ///
+    /// # Examples
+    /// ```javascript
+    /// qrl(() => import("./handlers"), "onSubmit");
+    /// ```
+    pub fn from_import(source: &str, export_name: &str, qrl_type: QrlType) -> Self {
+        Self {
+            rel_path: PathBuf::from(source),
+            display_name: export_name.into(),
+            qrl_type,
+            import_path: source.into(),
+            dev: None,
         }
     }
 
@@ -53,34 +187,16 @@ impl Qrl {
     /// to the relevant symbol_id if it exists.
     ///
     fn make_ref_id(
-        qrl_type: &QrlType,
+        &self,
         ctx: &mut TraverseCtx<'_, ()>,
         symbols_by_name: &mut HashMap<String, SymbolId>,
         import_by_symbol: &mut HashMap<SymbolId, Import>,
     ) -> ReferenceId {
-        match qrl_type {
-            QrlType::Qrl | QrlType::IndexedQrl(_) => {
-                // `qrl` is ALWAYS part of newly created expression, even if `$` was not used in the initial script.
-                // If `qrl` was not explicitly imported in the original script, we need to synthesize both a SymbolId and an Import.
-                let qrl_symbol_id = if !symbols_by_name.contains_key(QRL) {
-                    let symbol_id = ctx.scoping_mut().create_symbol(
-                        SPAN,
-                        QRL,
-                        SymbolFlags::Import,
-                        ScopeId::new(0),
-                        NodeId::DUMMY,
-                    );
-                    let import = Import::new(vec!["qrl".into()], QWIK_CORE_SOURCE);
-                    symbols_by_name.insert(QRL.to_string(), symbol_id);
-                    import_by_symbol.insert(symbol_id, import);
-                    symbol_id
-                } else {
-                    *symbols_by_name.get(QRL).unwrap() // This should never fail based on the call above.
-                };
-
-                ctx.create_bound_reference(qrl_symbol_id, ReferenceFlags::None)
+        match self.qrl_type.wrapper_prefix() {
+            None => {
+                Self::make_qrl_ref_id(self.callee_name(), ctx, symbols_by_name, import_by_symbol)
             }
-            QrlType::PrefixedQrl(name) => {
+            Some(name) => {
                 if let Some(symbol_id) = symbols_by_name.get(name) {
                     ctx.create_bound_reference(*symbol_id, ReferenceFlags::None)
                 } else {
@@ -90,6 +206,35 @@ impl Qrl {
         }
     }
 
+    /// Creates a reference id for `qrl` or `qrlDEV`, synthesizing the import of the function if the
+    /// original script did not import it.
+    fn make_qrl_ref_id(
+        callee: &'static str,
+        ctx: &mut TraverseCtx<'_, ()>,
+        symbols_by_name: &mut HashMap<String, SymbolId>,
+        import_by_symbol: &mut HashMap<SymbolId, Import>,
+    ) -> ReferenceId {
+        // `qrl` is ALWAYS part of newly created expression, even if `$` was not used in the initial script.
+        // If `qrl` was not explicitly imported in the original script, we need to synthesize both a SymbolId and an Import.
+        let qrl_symbol_id = if !symbols_by_name.contains_key(callee) {
+            let symbol_id = ctx.scoping_mut().create_symbol(
+                SPAN,
+                callee,
+                SymbolFlags::Import,
+                ScopeId::new(0),
+                NodeId::DUMMY,
+            );
+            let import = Import::new(vec![callee.into()], QWIK_CORE_SOURCE);
+            symbols_by_name.insert(callee.to_string(), symbol_id);
+            import_by_symbol.insert(symbol_id, import);
+            symbol_id
+        } else {
+            *symbols_by_name.get(callee).unwrap() // This should never fail based on the call above.
+        };
+
+        ctx.create_bound_reference(qrl_symbol_id, ReferenceFlags::None)
+    }
+
     /// Creates a `qrl` identifier.
     ///
     /// # Examples
@@ -108,21 +253,14 @@ impl Qrl {
         import_by_symbol: &mut HashMap<SymbolId, Import>,
     ) -> IdentifierReference<'a> {
         let ast = ctx.ast;
-        match &self.qrl_type {
-            QrlType::Qrl | QrlType::IndexedQrl(_) => {
-                let ref_id =
-                    Self::make_ref_id(&self.qrl_type, ctx, symbols_by_name, import_by_symbol);
-                ast.identifier_reference_with_reference_id(SPAN, QRL, ref_id)
-            }
-            QrlType::PrefixedQrl(prefix) => {
-                let ref_id =
-                    Self::make_ref_id(&self.qrl_type, ctx, symbols_by_name, import_by_symbol);
-                ast.identifier_reference_with_reference_id(
-                    SPAN,
-                    ast.atom(&format!("{}{}", prefix, QRL_SUFFIX)),
-                    ref_id,
-                )
-            }
+        let ref_id = self.make_ref_id(ctx, symbols_by_name, import_by_symbol);
+        match self.qrl_type.wrapper_prefix() {
+            None => ast.identifier_reference_with_reference_id(SPAN, self.callee_name(), ref_id),
+            Some(prefix) => ast.identifier_reference_with_reference_id(
+                SPAN,
+                ast.atom(&format!("{}{}", prefix, QRL_SUFFIX)),
+                ref_id,
+            ),
         }
     }
 
@@ -140,14 +278,9 @@ impl Qrl {
     /// ```
     ///
     fn into_arrow_function<'a>(&self, ast_builder: &AstBuilder<'a>) -> ArrowFunctionExpression<'a> {
-        let filename = format!(
-            "./{}.js",
-            self.rel_path.file_name().unwrap().to_string_lossy()
-        );
-
         // Function Body /////////
         let mut statements = ast_builder.vec_with_capacity(1);
-        statements.push(ast_builder.create_simple_import(filename.as_ref()));
+        statements.push(ast_builder.create_simple_import(&self.import_path));
         let function_body = ast_builder.function_body(SPAN, ast_builder.vec(), statements);
         let func_params = ast_builder.formal_parameters(
             SPAN,
@@ -168,7 +301,11 @@ impl Qrl {
         )
     }
 
-    fn into_arguments<'a>(&self, ast_builder: &AstBuilder<'a>) -> OxcVec<'a, Argument<'a>> {
+    fn into_arguments<'a>(
+        &self,
+        ast_builder: &AstBuilder<'a>,
+        captures: Option<Expression<'a>>,
+    ) -> OxcVec<'a, Argument<'a>> {
         let allocator = ast_builder.allocator;
 
         // ARG: Display name string literal ////////
@@ -179,12 +316,22 @@ impl Qrl {
         );
 
         let mut args = ast_builder.vec_with_capacity(2);
-        let arrow_function = self.into_arrow_function(ast_builder);
-        args.push(Argument::ArrowFunctionExpression(OxcBox::new_in(
-            arrow_function,
-            allocator,
-        )));
+        if !matches!(self.qrl_type, QrlType::NoopQrl(_)) {
+            let arrow_function = self.into_arrow_function(ast_builder);
+            args.push(Argument::ArrowFunctionExpression(OxcBox::new_in(
+                arrow_function,
+                allocator,
+            )));
+        }
         args.push(Argument::StringLiteral(display_name_arg));
+        if let Some(dev) = &self.dev {
+            args.push(dev.to_expression(ast_builder).into());
+        }
+
+        // ARG: Captures of a stripped segment ////////
+        if let Some(captures) = captures {
+            args.push(captures.into());
+        }
 
         args
     }
@@ -195,15 +342,24 @@ impl Qrl {
         symbols_by_name: &mut HashMap<String, SymbolId>,
         import_by_symbol: &mut HashMap<SymbolId, Import>,
     ) -> CallExpression<'a> {
-        let ast_builder = ctx.ast;
+        self.call_expression_with_captures(ctx, symbols_by_name, import_by_symbol, None)
+    }
 
-        let qrl_ref_id = Self::make_ref_id(&QrlType::Qrl, ctx, symbols_by_name, import_by_symbol);
-        let qrl = ast_builder.identifier_reference_with_reference_id(SPAN, QRL, qrl_ref_id);
-        let qrl_type = self.qrl_type.clone();
+    /// Like [Self::into_call_expression], passing the array of captured bindings of a stripped
+    /// segment as the last argument.
+    pub fn call_expression_with_captures<'a>(
+        &self,
+        ctx: &mut TraverseCtx<'a, ()>,
+        symbols_by_name: &mut HashMap<String, SymbolId>,
+        import_by_symbol: &mut HashMap<SymbolId, Import>,
+        captures: Option<Expression<'a>>,
+    ) -> CallExpression<'a> {
+        let ast_builder = ctx.ast;
 
-        let args = self
-            .into_arguments(&ast_builder)
-            .clone_in(ast_builder.allocator);
+        let callee = self.callee_name();
+        let qrl_ref_id = Self::make_qrl_ref_id(callee, ctx, symbols_by_name, import_by_symbol);
+        let qrl = ast_builder.identifier_reference_with_reference_id(SPAN, callee, qrl_ref_id);
+        let args = self.into_arguments(&ast_builder, captures);
         let qrl = OxcBox::new_in(qrl, ast_builder.allocator);
 
         let qrl_call_expr = ast_builder.call_expression(
@@ -214,13 +370,11 @@ impl Qrl {
             false,
         );
 
-        match qrl_type {
-            QrlType::Qrl | QrlType::IndexedQrl(_) => qrl_call_expr,
+        match self.qrl_type.wrapper_prefix() {
+            None => qrl_call_expr,
 
-            QrlType::PrefixedQrl(prefix) => {
-                let ref_id =
-                    Self::make_ref_id(&self.qrl_type, ctx, symbols_by_name, import_by_symbol);
-                Self::make_ref_id(&self.qrl_type, ctx, symbols_by_name, import_by_symbol);
+            Some(prefix) => {
+                let ref_id = self.make_ref_id(ctx, symbols_by_name, import_by_symbol);
                 let ident = OxcBox::new_in(
                     ast_builder.identifier_reference_with_reference_id(
                         SPAN,
@@ -294,12 +448,101 @@ impl Qrl {
 impl<'a> FromIn<'a, Qrl> for OxcVec<'a, Argument<'a>> {
     fn from_in(qrl: Qrl, allocator: &'a Allocator) -> Self {
         let ast_builder = AstBuilder::new(allocator);
-        qrl.into_arguments(&ast_builder)
+        qrl.into_arguments(&ast_builder, None)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::component::ImportId;
+    use oxc_codegen::Codegen;
+    use oxc_span::SourceType;
+
+    /// Renders the call that creates the QRL, with the callee named as in the full transform.
+    fn render(qrl: &Qrl, captures: &[&str]) -> String {
+        let allocator = Allocator::default();
+        let ast = AstBuilder::new(&allocator);
+        let captures = (!captures.is_empty()).then(|| {
+            ast.expression_array(
+                SPAN,
+                ast.vec_from_iter(captures.iter().map(|name| {
+                    ArrayExpressionElement::from(ast.expression_identifier(SPAN, ast.atom(name)))
+                })),
+            )
+        });
+        let call = ast.expression_call(
+            SPAN,
+            ast.expression_identifier(SPAN, qrl.callee_name()),
+            None::<OxcBox<TSTypeParameterInstantiation>>,
+            qrl.into_arguments(&ast, captures),
+            false,
+        );
+        let program = ast.program(
+            SPAN,
+            SourceType::mjs(),
+            "",
+            OxcVec::new_in(&allocator),
+            None,
+            OxcVec::new_in(&allocator),
+            ast.vec1(ast.statement_expression(SPAN, call)),
+        );
+        Codegen::new().build(&program).code.trim().to_string()
+    }
+
+    fn noop_qrl(prefix: Option<&str>) -> Qrl {
+        Qrl::new(
+            "./test.tsx_App_component_button_onClick_zBbHWn4e8Cg",
+            "App_component_button_onClick_zBbHWn4e8Cg",
+            QrlType::NoopQrl(prefix.map(str::to_string)),
+        )
+    }
+
+    #[test]
+    fn test_noop_qrl() {
+        assert_eq!(
+            render(&noop_qrl(None), &[]),
+            r#"_noopQrl("App_component_button_onClick_zBbHWn4e8Cg");"#
+        );
+        assert_eq!(
+            render(&noop_qrl(None), &["count", "store"]),
+            r#"_noopQrl("App_component_button_onClick_zBbHWn4e8Cg", [count, store]);"#
+        );
+        assert_eq!(
+            noop_qrl(None).import().names(),
+            &vec![ImportId::Named(NOOP_QRL.to_string())]
+        );
+        assert_eq!(
+            noop_qrl(Some("server")).import().names(),
+            &vec![
+                ImportId::Named("serverQrl".to_string()),
+                ImportId::Named(NOOP_QRL.to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_noop_qrl_dev() {
+        let qrl = noop_qrl(None).with_dev(QrlDev {
+            file: "test.tsx".to_string(),
+            lo: 10,
+            hi: 20,
+            display_name: "test.tsx_App_component_button_onClick".to_string(),
+        });
+        assert_eq!(
+            render(&qrl, &["count"]),
+            r#"_noopQrlDEV("App_component_button_onClick_zBbHWn4e8Cg", {
+	file: "test.tsx",
+	lo: 10,
+	hi: 20,
+	displayName: "test.tsx_App_component_button_onClick"
+}, [count]);"#
+        );
+        assert_eq!(
+            qrl.import().names(),
+            &vec![ImportId::Named(NOOP_QRL_DEV.to_string())]
+        );
+    }
 
     // #[test]
     // fn test_qurl() {
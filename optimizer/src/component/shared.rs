@@ -3,7 +3,7 @@ use crate::import_clean_up::ImportCleanUp;
 use oxc_allocator::{Allocator, FromIn};
 use oxc_ast::ast::{ImportDeclarationSpecifier, ImportOrExportKind, Statement};
 use oxc_ast::AstBuilder;
-use oxc_span::{Atom, SPAN};
+use oxc_span::SPAN;
 use serde::{Deserialize, Serialize};
 use std::convert::Into;
 use std::path::PathBuf;
@@ -11,9 +11,22 @@ use std::path::PathBuf;
 pub const QWIK_CORE_SOURCE: &str = "@qwik.dev/core";
 pub const JSX_SORTED_NAME: &str = "_jsxSorted";
 pub const JSX_SPLIT_NAME: &str = "_jsxSplit";
+pub const FRAGMENT_NAME: &str = "Fragment";
+pub const WRAP_PROP_NAME: &str = "_wrapProp";
+pub const FN_SIGNAL_NAME: &str = "_fnSignal";
 pub const MARKER_SUFFIX: &str = "$";
 pub const QRL: &str = "qrl";
+pub const QRL_DEV: &str = "qrlDEV";
+pub const INLINED_QRL: &str = "inlinedQrl";
+pub const INLINED_QRL_DEV: &str = "inlinedQrlDEV";
+pub const NOOP_QRL: &str = "_noopQrl";
+pub const NOOP_QRL_DEV: &str = "_noopQrlDEV";
+pub const REST_PROPS_NAME: &str = "_restProps";
+pub const COMPONENT_MARKER: &str = "component$";
+pub const SYNC_MARKER: &str = "sync$";
+pub const QRL_SYNC_NAME: &str = "_qrlSync";
 pub const QRL_SUFFIX: &str = "Qrl";
+pub const AUTO_EXPORT_PREFIX: &str = "_auto_";
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum ImportId {
@@ -29,21 +42,12 @@ impl From<&str> for ImportId {
     }
 }
 
-fn replace_marker_with_qrl(name: Atom<'_>) -> String {
-    let name = name.to_string();
-    if let Some(qrl_call) = name.strip_suffix(MARKER_SUFFIX) {
-        format!("{}{}", qrl_call, QRL_SUFFIX)
-    } else {
-        name
-    }
-}
-
 impl From<&ImportDeclarationSpecifier<'_>> for ImportId {
     fn from(value: &ImportDeclarationSpecifier<'_>) -> Self {
         match value {
             ImportDeclarationSpecifier::ImportSpecifier(specifier) => {
-                let imported = replace_marker_with_qrl(specifier.imported.name());
-                let local_name = replace_marker_with_qrl(specifier.local.name);
+                let imported = specifier.imported.name().to_string();
+                let local_name = specifier.local.name.to_string();
 
                 if imported == local_name {
                     ImportId::Named(imported)
@@ -115,6 +119,14 @@ impl Import {
         }
     }
 
+    pub fn names(&self) -> &[ImportId] {
+        &self.names
+    }
+
+    pub fn source(&self) -> &PathBuf {
+        &self.source
+    }
+
     pub fn into_statement<'a>(&self, allocator: &'a Allocator) -> Statement<'a> {
         let ast_builder = AstBuilder::new(allocator);
         ast_builder.create_import_statement(self.names.clone(), self.source.to_string_lossy())
@@ -11,6 +11,9 @@ pub enum Error {
     #[error("Unsupported language from SourceType: {0}")]
     UnsupportedLanguage(String),
 
+    #[error("Internal optimizer failure: {0}")]
+    InternalFailure(String),
+
     #[error(transparent)]
     IO(#[from] std::io::Error),
 
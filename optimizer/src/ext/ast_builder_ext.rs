@@ -11,6 +11,7 @@ pub trait AstBuilderExt<'a> {
         source: U,
     ) -> Statement<'a>;
     fn create_export_statement(self, name: &str, source: &str) -> Statement<'a>;
+    fn create_local_export_statement(self, local: &str, exported: &str) -> Statement<'a>;
 
     fn create_simple_import(self, name: &str) -> Statement<'a>;
 }
@@ -61,6 +62,23 @@ impl<'a> AstBuilderExt<'a> for AstBuilder<'a> {
         Statement::ExportNamedDeclaration(export_decl)
     }
 
+    fn create_local_export_statement(self, local: &str, exported: &str) -> Statement<'a> {
+        let local_name = self.module_export_name_identifier_reference(SPAN, self.atom(local));
+        let exported = self.module_export_name_identifier_name(SPAN, self.atom(exported));
+        let export_specifier =
+            self.export_specifier(SPAN, local_name, exported, ImportOrExportKind::Value);
+        let export_decl = self.alloc_export_named_declaration(
+            SPAN,
+            None,
+            self.vec1(export_specifier),
+            None,
+            ImportOrExportKind::Value,
+            None::<OxcBox<'a, WithClause<'a>>>,
+        );
+
+        Statement::ExportNamedDeclaration(export_decl)
+    }
+
     fn create_simple_import(self, name: &str) -> Statement<'a> {
         let raw: Atom = self.atom(&format!(r#""{}""#, name));
         let source = self.expression_string_literal(SPAN, self.atom(name), Some(raw));
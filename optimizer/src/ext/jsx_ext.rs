@@ -0,0 +1,278 @@
+use crate::component::MARKER_SUFFIX;
+use oxc_ast::ast::{JSXAttributeName, JSXText};
+use oxc_syntax::xml_entities::XML_ENTITIES;
+
+/// Two-way bindings: the `bind:` prop, the prop it sets and the runtime handler that writes back.
+const BINDINGS: [(&str, &str, &str); 2] = [
+    ("bind:value", "value", "_val"),
+    ("bind:checked", "checked", "_chk"),
+];
+
+/// JSX event prop prefixes and the attribute prefixes the runtime expects in their place.
+const EVENT_PREFIXES: [(&str, &str); 3] = [
+    ("document:on", "q-d:"),
+    ("window:on", "q-w:"),
+    ("on", "q-e:"),
+];
+
+pub trait JSXAttributeNameExt {
+    /// The attribute name as written, including its namespace, e.g. `xlink:href` or `q:slot`.
+    fn full_name(&self) -> String;
+
+    /// Whether the name can be used as an identifier property key, e.g. `class` but not
+    /// `bind:value` or `aria-label`.
+    fn is_identifier_key(&self) -> bool;
+
+    /// Whether the prop value is lazy loaded, e.g. `onClick$` or `document:onScroll$`.
+    fn is_qrl_prop(&self) -> bool;
+
+    /// The attribute an event handler prop of an intrinsic element is rendered as, e.g.
+    /// `onClick$` becomes `q-e:click`, `document:onScroll$` becomes `q-d:scroll` and
+    /// `window:onResize$` becomes `q-w:resize`.
+    ///
+    /// Event names are lower-cased, unless they start with a dash, in which case their camelCase is
+    /// preserved as dash-case: `on-myEvent$` becomes `q-e:my-event`.
+    fn event_attribute_name(&self) -> Option<String>;
+
+    /// For `bind:value` and `bind:checked`, the prop that is bound and the name of the runtime
+    /// handler that writes the input's value back to the signal.
+    fn binding(&self) -> Option<(&'static str, &'static str)>;
+}
+
+impl JSXAttributeNameExt for JSXAttributeName<'_> {
+    fn full_name(&self) -> String {
+        match self {
+            JSXAttributeName::Identifier(id) => id.name.to_string(),
+            JSXAttributeName::NamespacedName(name) => name.to_string(),
+        }
+    }
+
+    fn is_identifier_key(&self) -> bool {
+        match self {
+            JSXAttributeName::Identifier(id) => {
+                oxc_syntax::identifier::is_identifier_name(&id.name)
+            }
+            JSXAttributeName::NamespacedName(_) => false,
+        }
+    }
+
+    fn is_qrl_prop(&self) -> bool {
+        self.full_name().ends_with(MARKER_SUFFIX)
+    }
+
+    fn event_attribute_name(&self) -> Option<String> {
+        let full_name = self.full_name();
+        let name = full_name.strip_suffix(MARKER_SUFFIX)?;
+        let (event, prefix) = EVENT_PREFIXES.iter().find_map(|(jsx_prefix, prefix)| {
+            name.strip_prefix(jsx_prefix)
+                .filter(|event| !event.is_empty())
+                .map(|event| (event, prefix))
+        })?;
+        let event = if event == "DOMContentLoaded" {
+            "-d-o-m-content-loaded".to_string()
+        } else if let Some(event) = event.strip_prefix('-') {
+            to_dash_case(event)
+        } else {
+            event.to_lowercase()
+        };
+        Some(format!("{prefix}{event}"))
+    }
+
+    fn binding(&self) -> Option<(&'static str, &'static str)> {
+        let JSXAttributeName::NamespacedName(_) = self else {
+            return None;
+        };
+        let full_name = self.full_name();
+        BINDINGS
+            .iter()
+            .find(|(name, _, _)| *name == full_name)
+            .map(|(_, prop, handler)| (*prop, *handler))
+    }
+}
+
+pub trait JSXTextExt {
+    /// The text as it is rendered, following the JSX whitespace rules: lines are trimmed and
+    /// joined with a single space, lines that are only whitespace are dropped, and whitespace
+    /// within a line is kept. HTML entities such as `&nbsp;` and `&#123;` are decoded.
+    ///
+    /// Returns `None` when nothing but whitespace remains.
+    fn rendered_text(&self) -> Option<String>;
+}
+
+impl JSXTextExt for JSXText<'_> {
+    fn rendered_text(&self) -> Option<String> {
+        let value = self.value.replace("\r\n", "\n").replace('\r', "\n");
+        let lines: Vec<&str> = value.split('\n').collect();
+        let last_non_empty_line = lines
+            .iter()
+            .rposition(|line| line.chars().any(|c| c != ' ' && c != '\t'))
+            .unwrap_or(0);
+        let mut text = String::new();
+        for (index, line) in lines.iter().enumerate() {
+            let mut line = *line;
+            if index > 0 {
+                line = line.trim_start_matches([' ', '\t']);
+            }
+            if index < lines.len() - 1 {
+                line = line.trim_end_matches([' ', '\t']);
+            }
+            if line.is_empty() {
+                continue;
+            }
+            text.push_str(&line.replace('\t', " "));
+            if index != last_non_empty_line {
+                text.push(' ');
+            }
+        }
+        (!text.is_empty()).then(|| decode_entities(&text))
+    }
+}
+
+/// Replaces HTML entities such as `&amp;`, `&#123;` and `&#x7B;` with the characters they encode.
+/// Anything that is not a known entity is left as written.
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest[1..].find(';').and_then(|end| {
+            let name = &rest[1..end + 1];
+            let c = match name.strip_prefix('#') {
+                Some(code) => match code.strip_prefix(['x', 'X']) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => code.parse::<u32>().ok(),
+                }
+                .and_then(char::from_u32),
+                None => XML_ENTITIES.get(name).copied(),
+            };
+            c.map(|c| (c, end + 2))
+        });
+        match entity {
+            Some((c, len)) => {
+                decoded.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn to_dash_case(input: &str) -> String {
+    input.chars().fold(String::new(), |mut acc, c| {
+        if c.is_ascii_uppercase() {
+            acc.push('-');
+            acc.push(c.to_ascii_lowercase());
+        } else {
+            acc.push(c);
+        }
+        acc
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxc_allocator::Allocator;
+    use oxc_ast::AstBuilder;
+    use oxc_span::SPAN;
+
+    fn attribute_name<'a>(ast: &AstBuilder<'a>, name: &str) -> JSXAttributeName<'a> {
+        match name.split_once(':') {
+            Some((namespace, name)) => ast.jsx_attribute_name_namespaced_name(
+                SPAN,
+                ast.jsx_identifier(SPAN, ast.atom(namespace)),
+                ast.jsx_identifier(SPAN, ast.atom(name)),
+            ),
+            None => ast.jsx_attribute_name_identifier(SPAN, ast.atom(name)),
+        }
+    }
+
+    fn event_name(name: &str) -> Option<String> {
+        let allocator = Allocator::default();
+        attribute_name(&AstBuilder::new(&allocator), name).event_attribute_name()
+    }
+
+    #[test]
+    fn test_event_attribute_names() {
+        assert_eq!(event_name("onClick$"), Some("q-e:click".to_string()));
+        assert_eq!(event_name("onDblClick$"), Some("q-e:dblclick".to_string()));
+        assert_eq!(
+            event_name("document:onScroll$"),
+            Some("q-d:scroll".to_string())
+        );
+        assert_eq!(
+            event_name("window:onResize$"),
+            Some("q-w:resize".to_string())
+        );
+        assert_eq!(event_name("on-myEvent$"), Some("q-e:my-event".to_string()));
+        assert_eq!(
+            event_name("document:onDOMContentLoaded$"),
+            Some("q-d:-d-o-m-content-loaded".to_string())
+        );
+    }
+
+    #[test]
+    fn test_non_event_attribute_names() {
+        assert_eq!(event_name("onClick"), None);
+        assert_eq!(event_name("on$"), None);
+        assert_eq!(event_name("custom$"), None);
+        assert_eq!(event_name("bind:value"), None);
+    }
+
+    #[test]
+    fn test_bindings() {
+        let allocator = Allocator::default();
+        let ast = AstBuilder::new(&allocator);
+        assert_eq!(
+            attribute_name(&ast, "bind:value").binding(),
+            Some(("value", "_val"))
+        );
+        assert_eq!(
+            attribute_name(&ast, "bind:checked").binding(),
+            Some(("checked", "_chk"))
+        );
+        assert_eq!(attribute_name(&ast, "bind:other").binding(), None);
+        assert_eq!(attribute_name(&ast, "value").binding(), None);
+    }
+
+    fn rendered_text(value: &str) -> Option<String> {
+        let allocator = Allocator::default();
+        let ast = AstBuilder::new(&allocator);
+        ast.jsx_text(SPAN, ast.atom(value), None).rendered_text()
+    }
+
+    #[test]
+    fn test_jsx_text_whitespace() {
+        assert_eq!(rendered_text(" "), Some(" ".to_string()));
+        assert_eq!(rendered_text("  a  b  "), Some("  a  b  ".to_string()));
+        assert_eq!(rendered_text("\n    "), None);
+        assert_eq!(
+            rendered_text("\n    Hello\n    world  \n"),
+            Some("Hello world".to_string())
+        );
+        assert_eq!(rendered_text("a\r\n\r\n\tb "), Some("a b ".to_string()));
+        assert_eq!(
+            rendered_text("\tindented\t"),
+            Some(" indented ".to_string())
+        );
+    }
+
+    #[test]
+    fn test_jsx_text_entities() {
+        assert_eq!(
+            rendered_text("Tom&nbsp;&amp;&nbsp;Jerry"),
+            Some("Tom\u{a0}&\u{a0}Jerry".to_string())
+        );
+        assert_eq!(rendered_text("&#123;&#x7D;"), Some("{}".to_string()));
+        assert_eq!(
+            rendered_text("a & b &unknown; &"),
+            Some("a & b &unknown; &".to_string())
+        );
+    }
+}
@@ -1,5 +1,5 @@
 mod ast_builder_ext;
-mod expression_ext;
+mod jsx_ext;
 
 pub(crate) use ast_builder_ext::*;
-pub(crate) use expression_ext::*;
+pub(crate) use jsx_ext::*;
@@ -1,6 +1,25 @@
-use oxc_ast::ast::Statement;
+use oxc_ast::ast::{Expression, Statement};
 use oxc_semantic::SymbolId;
 
+/// Built-in constructors whose instances Qwik knows how to serialize.
+const SERIALIZABLE_CONSTRUCTORS: [&str; 12] = [
+    "Date",
+    "URL",
+    "URLSearchParams",
+    "RegExp",
+    "Map",
+    "Set",
+    "Error",
+    "FormData",
+    "Uint8Array",
+    "Promise",
+    "Array",
+    "Object",
+];
+
+/// Zero-argument methods that hand back an iterator rather than a collection.
+const ITERATOR_METHODS: [&str; 3] = ["entries", "keys", "values"];
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum IllegalCodeType {
     Class(SymbolId, Option<String>),
@@ -34,6 +53,155 @@ impl IllegalCodeType {
     }
 }
 
+/// Values that are legal to reference from a Qrl($) scope but that cannot survive serialization.
+///
+/// Unlike [IllegalCodeType], these are not removed from the source; they are only reported.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum UnsafeCaptureType {
+    This,
+    Arguments,
+    /// The captured identifier and the name of the class it was constructed from.
+    ClassInstance(String, String),
+    Iterator(String),
+    ReassignedLet(String),
+    /// A binding declared outside of a `sync$` function, which is serialized on its own.
+    SyncReference(String),
+}
+
+impl UnsafeCaptureType {
+    pub(crate) fn identifier(&self) -> String {
+        match self {
+            UnsafeCaptureType::This => "this".to_string(),
+            UnsafeCaptureType::Arguments => "arguments".to_string(),
+            UnsafeCaptureType::ClassInstance(name, _) => name.clone(),
+            UnsafeCaptureType::Iterator(name) => name.clone(),
+            UnsafeCaptureType::ReassignedLet(name) => name.clone(),
+            UnsafeCaptureType::SyncReference(name) => name.clone(),
+        }
+    }
+
+    pub(crate) fn message(&self) -> String {
+        let id = self.identifier();
+        match self {
+            UnsafeCaptureType::This | UnsafeCaptureType::Arguments => format!(
+                "'{id}' can not be captured inside a Qrl($) scope because it refers to the enclosing function"
+            ),
+            UnsafeCaptureType::ClassInstance(_, class) => format!(
+                "Reference to identifier '{id}' inside a Qrl($) scope captures an instance of '{class}', which can not be serialized"
+            ),
+            UnsafeCaptureType::Iterator(_) => format!(
+                "Reference to identifier '{id}' inside a Qrl($) scope captures a generator or iterator, which can not be serialized"
+            ),
+            UnsafeCaptureType::ReassignedLet(_) => format!(
+                "Identifier '{id}' is reassigned after being captured by a Qrl($) scope; the Qrl will only see the value at the time of capture"
+            ),
+            UnsafeCaptureType::SyncReference(_) => format!(
+                "Reference to identifier '{id}' can not be used inside sync$, which is serialized as source and can only use its own bindings and globals"
+            ),
+        }
+    }
+
+    /// Whether the capture is certain to fail at runtime, as opposed to merely being suspicious.
+    pub(crate) fn is_error(&self) -> bool {
+        match self {
+            UnsafeCaptureType::This => true,
+            UnsafeCaptureType::Arguments => true,
+            UnsafeCaptureType::Iterator(_) => true,
+            UnsafeCaptureType::ClassInstance(_, _) => false,
+            UnsafeCaptureType::ReassignedLet(_) => false,
+            UnsafeCaptureType::SyncReference(_) => true,
+        }
+    }
+}
+
+/// Arguments of `$` marker calls that are not inline functions and so can not become a segment.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum QrlArgumentType {
+    /// The marker name and an identifier declared inside an enclosing function, which the
+    /// extracted segment would have no way to reach.
+    UnreachableIdentifier(String, String),
+    /// The marker name and a module-level identifier that the segment merely re-exports.
+    ModuleIdentifier(String, String),
+    /// The marker name, called with an argument that is neither a function nor an identifier.
+    NotAFunction(String),
+}
+
+impl QrlArgumentType {
+    pub(crate) fn message(&self) -> String {
+        match self {
+            QrlArgumentType::UnreachableIdentifier(marker, id) => format!(
+                "'{marker}' was called with the local identifier '{id}' instead of a function, which a segment can not reach; no segment is extracted for it"
+            ),
+            QrlArgumentType::ModuleIdentifier(marker, id) => format!(
+                "'{marker}' was called with '{id}' instead of an inline function; the segment will only re-export it"
+            ),
+            QrlArgumentType::NotAFunction(marker) => format!(
+                "'{marker}' was called with an expression that is not a function; no segment is extracted for it"
+            ),
+        }
+    }
+
+    pub(crate) fn is_error(&self) -> bool {
+        match self {
+            QrlArgumentType::UnreachableIdentifier(_, _) => true,
+            QrlArgumentType::ModuleIdentifier(_, _) => false,
+            QrlArgumentType::NotAFunction(_) => true,
+        }
+    }
+}
+
+/// Classifies a variable initializer as a value that would be unsafe to capture.
+pub(crate) trait UnsafeCapture {
+    /// `name` is the identifier being bound; `is_generator` reports whether a callee refers to a
+    /// generator function, so that calls producing generator objects can be recognized.
+    fn unsafe_capture(
+        &self,
+        name: &str,
+        is_generator: impl Fn(&Expression) -> bool,
+    ) -> Option<UnsafeCaptureType>;
+}
+
+impl UnsafeCapture for Expression<'_> {
+    fn unsafe_capture(
+        &self,
+        name: &str,
+        is_generator: impl Fn(&Expression) -> bool,
+    ) -> Option<UnsafeCaptureType> {
+        match self.without_parentheses() {
+            Expression::NewExpression(new) => match &new.callee {
+                Expression::Identifier(class)
+                    if !SERIALIZABLE_CONSTRUCTORS.contains(&class.name.as_str()) =>
+                {
+                    Some(UnsafeCaptureType::ClassInstance(
+                        name.to_string(),
+                        class.name.to_string(),
+                    ))
+                }
+                _ => None,
+            },
+            Expression::CallExpression(call) => {
+                let is_generator_call = is_generator(&call.callee);
+                let is_iterator_call = match call.callee.without_parentheses() {
+                    Expression::StaticMemberExpression(member) => {
+                        let method = member.property.name.as_str();
+                        method == "matchAll"
+                            || (call.arguments.is_empty() && ITERATOR_METHODS.contains(&method))
+                    }
+                    Expression::ComputedMemberExpression(member) => {
+                        matches!(&member.expression, Expression::StaticMemberExpression(symbol)
+                            if symbol.object.is_specific_id("Symbol")
+                                && matches!(symbol.property.name.as_str(), "iterator" | "asyncIterator"))
+                    }
+                    _ => false,
+                };
+                (is_generator_call || is_iterator_call)
+                    .then(|| UnsafeCaptureType::Iterator(name.to_string()))
+            }
+            _ => None,
+        }
+    }
+}
+
 pub(crate) trait IllegalCode {
     fn is_illegal_code_in_qrl(&self) -> Option<IllegalCodeType>;
 }
@@ -119,4 +287,48 @@ mod tests {
             "Variable declaration should not be illegal code"
         );
     }
+
+    fn initializer<'a>(stmt: &'a Statement<'a>) -> &'a Expression<'a> {
+        match stmt {
+            Statement::VariableDeclaration(decl) => decl.declarations[0]
+                .init
+                .as_ref()
+                .expect("Should have an initializer"),
+            _ => panic!("Expected a variable declaration"),
+        }
+    }
+
+    #[test]
+    fn test_class_instance_is_unsafe_capture() {
+        let allocator = Allocator::default();
+        let stmt = parse_statement("const c = new Counter();", &allocator);
+        assert_eq!(
+            initializer(&stmt).unsafe_capture("c", |_| false),
+            Some(UnsafeCaptureType::ClassInstance(
+                "c".to_string(),
+                "Counter".to_string()
+            ))
+        );
+
+        let stmt = parse_statement("const d = new Date();", &allocator);
+        assert_eq!(initializer(&stmt).unsafe_capture("d", |_| false), None);
+    }
+
+    #[test]
+    fn test_iterator_is_unsafe_capture() {
+        let allocator = Allocator::default();
+        let expected = Some(UnsafeCaptureType::Iterator("i".to_string()));
+
+        let stmt = parse_statement("const i = items[Symbol.iterator]();", &allocator);
+        assert_eq!(initializer(&stmt).unsafe_capture("i", |_| false), expected);
+
+        let stmt = parse_statement("const i = map.entries();", &allocator);
+        assert_eq!(initializer(&stmt).unsafe_capture("i", |_| false), expected);
+
+        let stmt = parse_statement("const i = gen();", &allocator);
+        assert_eq!(initializer(&stmt).unsafe_capture("i", |_| true), expected);
+
+        let stmt = parse_statement("const i = Object.entries(obj);", &allocator);
+        assert_eq!(initializer(&stmt).unsafe_capture("i", |_| false), None);
+    }
 }
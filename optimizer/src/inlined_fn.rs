@@ -0,0 +1,291 @@
+use crate::component::{FN_SIGNAL_NAME, WRAP_PROP_NAME};
+use oxc_allocator::CloneIn;
+use oxc_ast::ast::*;
+use oxc_ast::AstBuilder;
+use oxc_ast_visit::{walk, walk_mut, Visit, VisitMut};
+use oxc_codegen::{Codegen, CodegenOptions};
+use oxc_semantic::{ReferenceId, Scoping};
+use oxc_span::{Atom, GetSpan, SPAN};
+use oxc_syntax::scope::ScopeFlags;
+
+/// Derived expressions whose minified source is longer than this are left as they are.
+const MAX_INLINED_FN_LENGTH: usize = 150;
+
+/// Rewrites a JSX prop or child expression that reads from a signal, a store or props, so that
+/// the runtime tracks the read instead of re-rendering the whole component.
+///
+/// - `store.name` becomes `_wrapProp(store, "name")`, and `sig.value` becomes `_wrapProp(sig)`.
+/// - A derived expression such as `store.count + 1` becomes
+///   `_fnSignal((p0) => p0.count + 1, [store], "p0.count+1")`.
+///
+/// Returns the name of the runtime helper the expression now calls, or `None` if it was left
+/// untouched because it does not read any local binding or can not be safely inlined.
+pub(crate) fn convert_reactive_expression<'a>(
+    builder: &AstBuilder<'a>,
+    scoping: &Scoping,
+    expr: &mut Expression<'a>,
+) -> Option<&'static str> {
+    let mut reads = ReactiveReads {
+        scoping,
+        deps: Vec::new(),
+        reads_member: false,
+        abort: false,
+    };
+    reads.visit_expression(expr);
+    if reads.abort || !reads.reads_member {
+        return None;
+    }
+
+    if let Some((object, property)) = wrappable_member(expr, &reads.deps) {
+        let mut arguments = builder.vec1(Argument::from(object.into_expression(builder)));
+        if property != "value" {
+            arguments.push(Argument::from(
+                builder.expression_string_literal(SPAN, property, None),
+            ));
+        }
+        *expr = call(builder, expr.span(), WRAP_PROP_NAME, arguments);
+        return Some(WRAP_PROP_NAME);
+    }
+
+    let mut body = expr.clone_in(builder.allocator);
+    let mut params = Params {
+        builder,
+        deps: &reads.deps,
+    };
+    params.visit_expression(&mut body);
+
+    let mut codegen = Codegen::new().with_options(CodegenOptions::minify());
+    codegen.print_expression(&body);
+    let source = codegen.into_source_text();
+    if source.len() > MAX_INLINED_FN_LENGTH {
+        return None;
+    }
+
+    let arrow = builder.expression_arrow_function(
+        SPAN,
+        true,
+        false,
+        None::<oxc_allocator::Box<TSTypeParameterDeclaration<'a>>>,
+        builder.formal_parameters(
+            SPAN,
+            FormalParameterKind::ArrowFormalParameters,
+            builder.vec_from_iter((0..reads.deps.len()).map(|index| {
+                builder.formal_parameter(
+                    SPAN,
+                    builder.vec(),
+                    builder.binding_pattern(
+                        builder.binding_pattern_kind_binding_identifier(
+                            SPAN,
+                            builder.atom(&param_name(index)),
+                        ),
+                        None::<oxc_allocator::Box<TSTypeAnnotation<'a>>>,
+                        false,
+                    ),
+                    None,
+                    false,
+                    false,
+                )
+            })),
+            None::<oxc_allocator::Box<BindingRestElement<'a>>>,
+        ),
+        None::<oxc_allocator::Box<TSTypeAnnotation<'a>>>,
+        builder.function_body(
+            SPAN,
+            builder.vec(),
+            builder.vec1(builder.statement_expression(SPAN, body)),
+        ),
+    );
+    let deps = builder.expression_array(
+        SPAN,
+        builder.vec_from_iter(
+            reads
+                .deps
+                .iter()
+                .map(|dep| ArrayExpressionElement::from(dep.into_expression(builder))),
+        ),
+    );
+    let source = builder.expression_string_literal(SPAN, builder.atom(&source), None);
+    *expr = call(
+        builder,
+        expr.span(),
+        FN_SIGNAL_NAME,
+        builder.vec_from_array([arrow.into(), deps.into(), source.into()]),
+    );
+    Some(FN_SIGNAL_NAME)
+}
+
+fn call<'a>(
+    builder: &AstBuilder<'a>,
+    span: oxc_span::Span,
+    callee: &'static str,
+    arguments: oxc_allocator::Vec<'a, Argument<'a>>,
+) -> Expression<'a> {
+    builder.expression_call(
+        span,
+        builder.expression_identifier(SPAN, callee),
+        None::<oxc_allocator::Box<TSTypeParameterInstantiation<'a>>>,
+        arguments,
+        false,
+    )
+}
+
+fn param_name(index: usize) -> String {
+    format!("p{index}")
+}
+
+/// Matches a plain `object.property` read of a local binding.
+fn wrappable_member<'a>(expr: &Expression<'a>, deps: &[Dep<'a>]) -> Option<(Dep<'a>, Atom<'a>)> {
+    let Expression::StaticMemberExpression(member) = expr.without_parentheses() else {
+        return None;
+    };
+    let Expression::Identifier(object) = &member.object else {
+        return None;
+    };
+    if member.optional {
+        return None;
+    }
+    deps.iter()
+        .find(|dep| dep.name == object.name)
+        .map(|dep| (*dep, member.property.name))
+}
+
+/// A local binding read by a reactive expression.
+#[derive(Clone, Copy)]
+struct Dep<'a> {
+    name: Atom<'a>,
+    reference_id: ReferenceId,
+}
+
+impl<'a> Dep<'a> {
+    fn into_expression(self, builder: &AstBuilder<'a>) -> Expression<'a> {
+        builder.expression_identifier_with_reference_id(SPAN, self.name, self.reference_id)
+    }
+}
+
+/// Collects the local bindings an expression reads, giving up on anything that has side effects
+/// or introduces a scope of its own.
+struct ReactiveReads<'s, 'a> {
+    scoping: &'s Scoping,
+    deps: Vec<Dep<'a>>,
+    reads_member: bool,
+    abort: bool,
+}
+
+impl ReactiveReads<'_, '_> {
+    fn is_local(&self, id: &IdentifierReference<'_>) -> bool {
+        id.reference_id
+            .get()
+            .and_then(|reference_id| self.scoping.get_reference(reference_id).symbol_id())
+            .is_some_and(|symbol_id| {
+                self.scoping.symbol_scope_id(symbol_id) != self.scoping.root_scope_id()
+            })
+    }
+
+    fn check_object(&mut self, object: &Expression<'_>) {
+        if let Expression::Identifier(id) = object.without_parentheses() {
+            self.reads_member |= self.is_local(id);
+        }
+    }
+}
+
+impl<'a> Visit<'a> for ReactiveReads<'_, 'a> {
+    fn visit_identifier_reference(&mut self, id: &IdentifierReference<'a>) {
+        if !self.is_local(id) || self.deps.iter().any(|dep| dep.name == id.name) {
+            return;
+        }
+        if let Some(reference_id) = id.reference_id.get() {
+            self.deps.push(Dep {
+                name: id.name,
+                reference_id,
+            });
+        }
+    }
+
+    fn visit_static_member_expression(&mut self, it: &StaticMemberExpression<'a>) {
+        self.check_object(&it.object);
+        walk::walk_static_member_expression(self, it);
+    }
+
+    fn visit_computed_member_expression(&mut self, it: &ComputedMemberExpression<'a>) {
+        self.check_object(&it.object);
+        walk::walk_computed_member_expression(self, it);
+    }
+
+    fn visit_call_expression(&mut self, _it: &CallExpression<'a>) {
+        self.abort = true;
+    }
+
+    fn visit_new_expression(&mut self, _it: &NewExpression<'a>) {
+        self.abort = true;
+    }
+
+    fn visit_tagged_template_expression(&mut self, _it: &TaggedTemplateExpression<'a>) {
+        self.abort = true;
+    }
+
+    fn visit_import_expression(&mut self, _it: &ImportExpression<'a>) {
+        self.abort = true;
+    }
+
+    fn visit_assignment_expression(&mut self, _it: &AssignmentExpression<'a>) {
+        self.abort = true;
+    }
+
+    fn visit_update_expression(&mut self, _it: &UpdateExpression<'a>) {
+        self.abort = true;
+    }
+
+    fn visit_await_expression(&mut self, _it: &AwaitExpression<'a>) {
+        self.abort = true;
+    }
+
+    fn visit_yield_expression(&mut self, _it: &YieldExpression<'a>) {
+        self.abort = true;
+    }
+
+    fn visit_arrow_function_expression(&mut self, _it: &ArrowFunctionExpression<'a>) {
+        self.abort = true;
+    }
+
+    fn visit_function(&mut self, _it: &Function<'a>, _flags: ScopeFlags) {
+        self.abort = true;
+    }
+
+    fn visit_class(&mut self, _it: &Class<'a>) {
+        self.abort = true;
+    }
+
+    fn visit_jsx_element(&mut self, _it: &JSXElement<'a>) {
+        self.abort = true;
+    }
+
+    fn visit_jsx_fragment(&mut self, _it: &JSXFragment<'a>) {
+        self.abort = true;
+    }
+
+    fn visit_this_expression(&mut self, _it: &ThisExpression) {
+        self.abort = true;
+    }
+}
+
+/// Replaces the reads of each dependency with the matching `p<index>` parameter.
+///
+/// Matching by name is safe because [ReactiveReads] rejects anything that could shadow a binding.
+struct Params<'b, 'a> {
+    builder: &'b AstBuilder<'a>,
+    deps: &'b [Dep<'a>],
+}
+
+impl<'a> VisitMut<'a> for Params<'_, 'a> {
+    fn visit_identifier_reference(&mut self, id: &mut IdentifierReference<'a>) {
+        if let Some(index) = self.deps.iter().position(|dep| dep.name == id.name) {
+            id.name = self.builder.atom(&param_name(index));
+        }
+    }
+
+    fn visit_object_property(&mut self, it: &mut ObjectProperty<'a>) {
+        // `{ store }` has to become `{ store: p0 }` rather than `{ p0 }`.
+        it.shorthand = false;
+        walk_mut::walk_object_property(self, it);
+    }
+}
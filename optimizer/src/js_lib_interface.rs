@@ -10,11 +10,13 @@ use crate::source::Source;
 use crate::transform::*;
 
 use crate::component::*;
+use oxc_span::Span;
 use serde::{Deserialize, Serialize};
 use std::iter::Sum;
 
 use std::cmp::Ordering;
 use std::hash::{DefaultHasher, Hasher};
+use std::panic::{self, AssertUnwindSafe};
 use std::path::{Path, PathBuf};
 use std::str;
 
@@ -55,6 +57,12 @@ pub struct TransformModulesOptions {
     pub strip_event_handlers: bool,
     pub reg_ctx_name: Option<Vec<String>>,
     pub is_server: Option<bool>,
+    /// Adds `data-qwik-inspector` attributes to JSX elements in dev mode.
+    pub jsx_inspector: Option<bool>,
+    /// Makes the transform of the input with this path panic, to exercise failure isolation.
+    #[cfg(test)]
+    #[serde(skip)]
+    pub panic_on_path: Option<String>,
 }
 
 #[derive(Debug, Serialize, Default)]
@@ -85,6 +93,13 @@ impl Sum<Self> for TransformOutput {
     }
 }
 
+#[derive(Debug, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckOutput {
+    pub segments: Vec<SegmentAnalysis>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
 #[derive(Debug, Serialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct TransformModule {
@@ -137,24 +152,34 @@ pub struct SourceLocation {
     end_col: usize,
 }
 
-// impl SourceLocation {
-//     pub fn from(source_map: &swc_common::SourceMap, span: swc_common::Span) -> Self {
-//         let start = source_map.lookup_char_pos(span.lo);
-//         let end = source_map.lookup_char_pos(span.hi);
-//         // - SWC's columns are exclusive, ours are inclusive (column - 1)
-//         // - SWC has 0-based columns, ours are 1-based (column + 1)
-//         // = +-0
-//
-//         Self {
-//             lo: span.lo.0 as usize,
-//             hi: span.hi.0 as usize,
-//             start_line: start.line,
-//             start_col: start.col_display + 1,
-//             end_line: end.line,
-//             end_col: end.col_display,
-//         }
-//     }
-// }
+impl SourceLocation {
+    /// Computes the line and column information of a `Span` within `source`.
+    ///
+    /// Lines and columns are 1-based; the end column is inclusive.
+    pub fn from_span(source: &str, span: Span) -> Self {
+        let position = |offset: usize| {
+            let before = &source[..offset.min(source.len())];
+            let line = before.matches('\n').count() + 1;
+            let col = before
+                .rfind('\n')
+                .map_or(before, |nl| &before[nl + 1..])
+                .chars()
+                .count();
+            (line, col)
+        };
+        let (start_line, start_col) = position(span.start as usize);
+        let (end_line, end_col) = position(span.end as usize);
+
+        Self {
+            lo: span.start as usize,
+            hi: span.end as usize,
+            start_line,
+            start_col: start_col + 1,
+            end_line,
+            end_col,
+        }
+    }
+}
 
 impl PartialOrd for SourceLocation {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
@@ -194,134 +219,225 @@ pub enum DiagnosticScope {
     Optimizer,
 }
 
-fn error_to_diagnostic(error: ProcessingFailure, path: &Path) -> Diagnostic {
+fn error_to_diagnostic(error: ProcessingFailure, path: &Path, source: &str) -> Diagnostic {
+    let highlights = Some(vec![SourceLocation::from_span(source, error.span())]);
+    let category = if error.is_error() {
+        DiagnosticCategory::Error
+    } else {
+        DiagnosticCategory::Warning
+    };
     let message = match error {
-        ProcessingFailure::IllegalCode(code) =>
-            format!(
-                "Reference to identifier '{id}' can not be used inside a Qrl($) scope because it's a {expr_type}",
-                id = code.identifier(), expr_type = code.expression_type()
-            )
+        ProcessingFailure::Parse(message, _) => message,
+        ProcessingFailure::Semantic(message, _) => message,
+        ProcessingFailure::IllegalCode(code, _) => format!(
+            "Reference to identifier '{id}' can not be used inside a Qrl($) scope because it's a {expr_type}",
+            id = code.identifier(), expr_type = code.expression_type()
+        ),
+        ProcessingFailure::UnsafeCapture(capture, _) => capture.message(),
+        ProcessingFailure::InvalidQrlArgument(argument, _) => argument.message(),
+        ProcessingFailure::DuplicateClass(_) => {
+            "Both 'class' and 'className' are set on the same element; the last one is used"
+                .to_string()
+        }
+        ProcessingFailure::Internal(message, _) => format!("Internal optimizer failure: {message}"),
     };
     Diagnostic {
-        category: DiagnosticCategory::Error,
+        category,
         code: None,
         file: path.to_string_lossy().to_string(),
         message,
-        highlights: None,
+        highlights,
         suggestions: None,
         scope: DiagnosticScope::Optimizer,
     }
 }
 
-pub fn transform_modules(config: TransformModulesOptions) -> Result<TransformOutput> {
-    let mut final_output = config
-        .input
-        .into_iter()
-        .map(|input| -> Result<Option<TransformOutput>> {
-            let path = Path::new(&input.path);
-            let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-            let relative_path = if path.is_relative() {
-                path.into()
-            } else {
-                pathdiff::diff_paths(path, &config.src_dir).ok_or_else(|| {
-                    Error::Generic(format!(
-                        "Path {} cannot be made relative to directory {}",
-                        path.to_string_lossy(),
-                        &config.src_dir
-                    ))
-                })?
-            }
-            .to_string_lossy()
-            .to_string();
-            let language = match ext {
-                "ts" => Language::Typescript,
-                "tsx" => Language::Typescript,
-                "js" => Language::Javascript,
-                "jsx" => Language::Javascript,
-                "mjs" => Language::Javascript,
-                "cjs" => Language::Javascript,
-                _ => return Ok(None),
-            };
-            let OptimizationResult {
-                optimized_app,
-                errors,
-            } = transform(
-                Source::from_source(
-                    input.code,
-                    language,
-                    Some(path.with_extension("").to_string_lossy().to_string()),
-                )?,
-                TransformOptions {
-                    minify: match config.minify {
-                        MinifyMode::Simplify => true,
-                        MinifyMode::None => false,
-                    },
-                    target: config.mode,
-                    transpile_ts: config.transpile_ts,
-                    transpile_jsx: config.transpile_jsx,
-                },
-            )?;
-            let mut hasher = DefaultHasher::new();
-            hasher.write(relative_path.as_bytes());
-            let mut modules = vec![TransformModule {
-                path: relative_path.clone(),
-                code: optimized_app.body,
-                map: None,
-                segment: None,
-                is_entry: false,
-                order: hasher.finish(),
-            }];
-            modules.extend(optimized_app.components.into_iter().map(|c| {
-                TransformModule {
-                    path: format!("{}.js", &c.id.local_file_name),
-                    code: c.code,
-                    map: None,
-                    segment: Some(SegmentAnalysis {
-                        origin: relative_path.clone(),
-                        name: c.id.symbol_name.clone(),
-                        entry: None,
-                        display_name: c.id.display_name,
-                        hash: c.id.hash,
-                        canonical_filename: PathBuf::from(&c.id.local_file_name)
-                            .file_name()
-                            .unwrap()
-                            .to_string_lossy()
-                            .to_string(),
-                        path: PathBuf::from(&c.id.local_file_name)
-                            .parent()
-                            .unwrap()
-                            .to_string_lossy()
-                            .to_string(),
-                        extension: "js".to_string(),
-                        parent: c.id.scope,
-                        ctx_kind: if c.id.symbol_name.starts_with("on") {
-                            SegmentKind::JSXProp
-                        } else {
-                            SegmentKind::Function
-                        },
-                        ctx_name: c.id.symbol_name,
-                        captures: false,
-                        loc: (0, 0),
-                    }),
-                    is_entry: true,
-                    order: c.id.sort_order,
-                }
-            }));
-            Ok(Some(TransformOutput {
-                modules,
-                diagnostics: errors
-                    .into_iter()
-                    .map(|e| error_to_diagnostic(e, &path))
-                    .collect(),
-                is_type_script: config.transpile_ts, // TODO: Set this flag correctly
-                is_jsx: config.transpile_jsx,        // TODO: Set this flag correctly
-            }))
+/// Transforms a single input module, converting any failure, including a panic, into an
+/// `Error` diagnostic for that module so the rest of the batch is unaffected.
+fn transform_module_isolated(
+    input: TransformModuleInput,
+    config: &TransformModulesOptions,
+    check_only: bool,
+) -> Option<TransformOutput> {
+    let file = input.path.clone();
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        #[cfg(test)]
+        if config.panic_on_path.as_ref() == Some(&input.path) {
+            panic!("Injected failure in {}", input.path);
+        }
+        transform_module(input, config, check_only)
+    }))
+    .unwrap_or_else(|payload| {
+        let reason = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        Err(Error::InternalFailure(reason))
+    })
+    .unwrap_or_else(|err| {
+        Some(TransformOutput {
+            diagnostics: vec![Diagnostic {
+                category: DiagnosticCategory::Error,
+                code: None,
+                file,
+                message: err.to_string(),
+                highlights: None,
+                suggestions: None,
+                scope: DiagnosticScope::Optimizer,
+            }],
+            ..TransformOutput::default()
         })
-        .sum::<Result<Option<TransformOutput>>>()?
-        .unwrap_or(TransformOutput::default());
+    })
+}
+
+/// Builds the root module and one module per extracted segment.
+fn app_modules(app: OptimizedApp, relative_path: &str) -> Vec<TransformModule> {
+    let mut hasher = DefaultHasher::new();
+    hasher.write(relative_path.as_bytes());
+    let mut modules = vec![TransformModule {
+        path: relative_path.to_string(),
+        code: app.body,
+        map: None,
+        segment: None,
+        is_entry: false,
+        order: hasher.finish(),
+    }];
+    modules.extend(app.components.into_iter().map(|c| {
+        TransformModule {
+            path: format!("{}.js", &c.id.local_file_name),
+            code: c.code,
+            map: None,
+            segment: Some(SegmentAnalysis {
+                origin: relative_path.to_string(),
+                name: c.id.symbol_name.clone(),
+                entry: None,
+                display_name: c.id.display_name,
+                hash: c.id.hash,
+                canonical_filename: PathBuf::from(&c.id.local_file_name)
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .to_string(),
+                path: PathBuf::from(&c.id.local_file_name)
+                    .parent()
+                    .unwrap()
+                    .to_string_lossy()
+                    .to_string(),
+                extension: "js".to_string(),
+                parent: c.id.scope,
+                ctx_kind: if c.id.symbol_name.starts_with("on") {
+                    SegmentKind::JSXProp
+                } else {
+                    SegmentKind::Function
+                },
+                ctx_name: c.id.symbol_name,
+                captures: false,
+                loc: (c.span.start, c.span.end),
+            }),
+            is_entry: true,
+            order: c.id.sort_order,
+        }
+    }));
+    modules
+}
+
+fn transform_module(
+    input: TransformModuleInput,
+    config: &TransformModulesOptions,
+    check_only: bool,
+) -> Result<Option<TransformOutput>> {
+    let path = Path::new(&input.path);
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    let relative_path = if path.is_relative() {
+        path.into()
+    } else {
+        pathdiff::diff_paths(path, &config.src_dir).ok_or_else(|| {
+            Error::Generic(format!(
+                "Path {} cannot be made relative to directory {}",
+                path.to_string_lossy(),
+                &config.src_dir
+            ))
+        })?
+    }
+    .to_string_lossy()
+    .to_string();
+    let language = match ext {
+        "ts" => Language::Typescript,
+        "tsx" => Language::Typescript,
+        "js" => Language::Javascript,
+        "jsx" => Language::Javascript,
+        "mjs" => Language::Javascript,
+        "cjs" => Language::Javascript,
+        _ => return Ok(None),
+    };
+    let OptimizationResult {
+        optimized_app,
+        errors,
+    } = transform(
+        Source::from_source(
+            &input.code,
+            language,
+            Some(path.with_extension("").to_string_lossy().to_string()),
+        )?,
+        TransformOptions {
+            minify: match config.minify {
+                MinifyMode::Simplify => true,
+                MinifyMode::None => false,
+            },
+            target: config.mode,
+            transpile_ts: config.transpile_ts,
+            transpile_jsx: config.transpile_jsx,
+            check_only,
+            dev_path: Some(input.dev_path.clone().unwrap_or(relative_path.clone())),
+            jsx_inspector: config.jsx_inspector.unwrap_or_default(),
+            strip_ctx_name: config.strip_ctx_name.clone().unwrap_or_default(),
+            strip_event_handlers: config.strip_event_handlers,
+        },
+    )?;
+    let modules = optimized_app
+        .map(|app| app_modules(app, &relative_path))
+        .unwrap_or_default();
+    Ok(Some(TransformOutput {
+        modules,
+        diagnostics: errors
+            .into_iter()
+            .map(|e| error_to_diagnostic(e, path, &input.code))
+            .collect(),
+        is_type_script: config.transpile_ts, // TODO: Set this flag correctly
+        is_jsx: config.transpile_jsx,        // TODO: Set this flag correctly
+    }))
+}
+
+fn process_modules(mut config: TransformModulesOptions, check_only: bool) -> TransformOutput {
+    let input = std::mem::take(&mut config.input);
+    let mut final_output = input
+        .into_iter()
+        .filter_map(|input| transform_module_isolated(input, &config, check_only))
+        .sum::<TransformOutput>();
 
     final_output.modules.sort_unstable_by_key(|key| key.order);
-    Ok(final_output)
+    final_output
+}
+
+pub fn transform_modules(config: TransformModulesOptions) -> Result<TransformOutput> {
+    Ok(process_modules(config, false))
+}
+
+/// Analyzes the input modules without generating any code.
+///
+/// This runs the same parsing, semantic analysis and capture checks as [transform_modules], but
+/// skips code generation and minification, which makes it cheap enough to run on every edit.
+pub fn check_modules(config: TransformModulesOptions) -> Result<CheckOutput> {
+    let output = process_modules(config, true);
+    Ok(CheckOutput {
+        segments: output
+            .modules
+            .into_iter()
+            .filter_map(|module| module.segment)
+            .collect(),
+        diagnostics: output.diagnostics,
+    })
 }
 
 #[cfg(test)]
@@ -425,6 +541,116 @@ mod tests {
         */
     }
 
+    #[test]
+    fn test_example_unsafe_captures() {
+        assert_valid_transform!(EntryStrategy::Segment);
+    }
+
+    #[test]
+    fn test_example_capture_written_in_closure() {
+        assert_valid_transform!(EntryStrategy::Segment);
+    }
+
+    #[test]
+    fn test_example_qrl_arguments() {
+        assert_valid_transform!(EntryStrategy::Segment);
+    }
+
+    #[test]
+    fn test_example_namespaced_jsx() {
+        assert_valid_transform!(EntryStrategy::Segment);
+    }
+
+    #[test]
+    fn test_example_reactive_props() {
+        assert_valid_transform!(EntryStrategy::Segment);
+    }
+
+    #[test]
+    fn test_example_event_props() {
+        assert_valid_transform!(EntryStrategy::Segment);
+    }
+
+    #[test]
+    fn test_example_bind() {
+        assert_valid_transform!(EntryStrategy::Segment);
+    }
+
+    #[test]
+    fn test_example_bind_dev() {
+        assert_valid_transform_dev!(EntryStrategy::Segment);
+    }
+
+    #[test]
+    fn test_example_invalid_segments_dev() {
+        assert_valid_transform_dev!(EntryStrategy::Segment);
+    }
+
+    #[test]
+    fn test_example_strip_segments() {
+        assert_valid_transform_stripped!(EntryStrategy::Segment, vec!["server".to_string()]);
+    }
+
+    #[test]
+    fn test_example_hoisted_jsx() {
+        assert_valid_transform!(EntryStrategy::Segment);
+    }
+
+    #[test]
+    fn test_example_jsx_flags() {
+        assert_valid_transform!(EntryStrategy::Segment);
+    }
+
+    #[test]
+    fn test_example_props_destructuring() {
+        assert_valid_transform!(EntryStrategy::Segment);
+    }
+
+    #[test]
+    fn test_example_jsx_text() {
+        assert_valid_transform!(EntryStrategy::Segment);
+    }
+
+    #[test]
+    fn test_example_text_only() {
+        assert_valid_transform!(EntryStrategy::Segment);
+    }
+
+    #[test]
+    fn test_example_jsx_split() {
+        assert_valid_transform!(EntryStrategy::Segment);
+    }
+
+    #[test]
+    fn test_example_fragment() {
+        assert_valid_transform!(EntryStrategy::Segment);
+    }
+
+    #[test]
+    fn test_example_ref() {
+        assert_valid_transform!(EntryStrategy::Segment);
+    }
+
+    #[test]
+    fn test_example_class_name() {
+        assert_valid_transform!(EntryStrategy::Segment);
+    }
+
+    #[test]
+    fn test_example_loop_handlers() {
+        assert_valid_transform!(EntryStrategy::Segment);
+    }
+
+    #[test]
+    fn test_example_sync_qrl() {
+        assert_valid_transform!(EntryStrategy::Segment);
+    }
+
+    #[test]
+    fn test_example_auto_export() {
+        assert_valid_transform!(EntryStrategy::Segment);
+    }
+
     #[test]
     fn test_example_jsx() {
         assert_valid_transform_debug!(EntryStrategy::Segment);
@@ -435,6 +661,238 @@ mod tests {
         assert_valid_transform_debug!(EntryStrategy::Segment);
     }
 
+    fn options_for(inputs: Vec<(&str, &str)>, mode: Target) -> TransformModulesOptions {
+        TransformModulesOptions {
+            input: inputs
+                .into_iter()
+                .map(|(path, code)| TransformModuleInput {
+                    path: path.to_string(),
+                    dev_path: None,
+                    code: code.to_string(),
+                })
+                .collect(),
+            src_dir: ".".to_string(),
+            root_dir: None,
+            minify: MinifyMode::None,
+            entry_strategy: EntryStrategy::Segment,
+            source_maps: false,
+            transpile_ts: false,
+            transpile_jsx: true,
+            preserve_filenames: false,
+            explicit_extensions: false,
+            mode,
+            scope: None,
+
+            core_module: None,
+            strip_exports: None,
+            strip_ctx_name: None,
+            strip_event_handlers: false,
+            reg_ctx_name: None,
+            is_server: None,
+            jsx_inspector: None,
+            panic_on_path: None,
+        }
+    }
+
+    fn transform_inputs(inputs: Vec<(&str, &str)>, mode: Target) -> TransformOutput {
+        transform_modules(options_for(inputs, mode)).unwrap()
+    }
+
+    #[test]
+    fn test_failing_module_is_isolated() {
+        let result = transform_modules(TransformModulesOptions {
+            panic_on_path: Some("broken.jsx".to_string()),
+            ..options_for(
+                vec![
+                    ("broken.jsx", "export const Broken = () => <p>broken</p>;"),
+                    ("working.jsx", "export const Text = () => <p>hi</p>;"),
+                ],
+                Target::Test,
+            )
+        })
+        .unwrap();
+
+        assert_eq!(result.modules.len(), 1);
+        assert_eq!(result.modules[0].path, "working.jsx");
+        assert_eq!(result.diagnostics.len(), 1);
+        assert_eq!(result.diagnostics[0].category, DiagnosticCategory::Error);
+        assert_eq!(result.diagnostics[0].file, "broken.jsx");
+        assert_eq!(
+            result.diagnostics[0].message,
+            "Internal optimizer failure: Injected failure in broken.jsx"
+        );
+    }
+
+    #[test]
+    fn test_parse_failure_returns_diagnostics_only() {
+        let result = transform_inputs(vec![("broken.js", "export const = ;")], Target::Dev);
+
+        assert!(result.modules.is_empty());
+        assert!(!result.diagnostics.is_empty());
+        assert!(result
+            .diagnostics
+            .iter()
+            .all(|d| d.category == DiagnosticCategory::Error && d.highlights.is_some()));
+    }
+
+    #[test]
+    fn test_dev_mode_emits_valid_segments() {
+        let code = r#"
+import { $, component$ } from '@qwik.dev/core';
+
+export const App = component$(() => {
+    function hola() {}
+    return $(() => hola());
+});
+"#;
+        let segment_names = |result: &TransformOutput| -> Vec<String> {
+            result
+                .modules
+                .iter()
+                .filter_map(|m| m.segment.as_ref().map(|s| s.display_name.clone()))
+                .collect()
+        };
+
+        let dev = transform_inputs(vec![("app.js", code)], Target::Dev);
+        assert_eq!(dev.diagnostics.len(), 1);
+        assert_eq!(dev.modules.len(), 2);
+        assert_eq!(segment_names(&dev), vec!["app.jsx_App_component"]);
+
+        let test = transform_inputs(vec![("app.js", code)], Target::Test);
+        assert_eq!(test.diagnostics.len(), 1);
+        assert_eq!(test.modules.len(), 3);
+    }
+
+    #[test]
+    fn test_dev_mode_jsx_source_info() {
+        let code = r#"import { component$ } from '@qwik.dev/core';
+export const App = component$(() => {
+    return <div>
+        <p class="é">hi</p>
+    </div>;
+});
+"#;
+        let module_code = |options: TransformModulesOptions| -> String {
+            transform_modules(options)
+                .unwrap()
+                .modules
+                .into_iter()
+                .filter(|m| m.segment.is_some())
+                .map(|m| m.code)
+                .collect()
+        };
+
+        let dev = module_code(options_for(vec![("app.jsx", code)], Target::Dev));
+        assert!(dev.contains("fileName: \"app.jsx\""));
+        assert!(dev.contains("lineNumber: 3,\n\tcolumnNumber: 12"));
+        assert!(dev.contains("lineNumber: 4,\n\tcolumnNumber: 9"));
+        assert!(!dev.contains("data-qwik-inspector"));
+
+        let mut options = options_for(vec![("app.jsx", code)], Target::Dev);
+        options.jsx_inspector = Some(true);
+        let inspected = module_code(options);
+        assert!(inspected.contains("\"data-qwik-inspector\": \"app.jsx:4:9\""));
+
+        let test = module_code(options_for(vec![("app.jsx", code)], Target::Test));
+        assert!(!test.contains("lineNumber"));
+    }
+
+    #[test]
+    fn test_dev_mode_qrl_dev_info() {
+        let code = r#"import { component$, useSignal } from '@qwik.dev/core';
+export const App = component$(() => {
+    const name = useSignal('');
+    return <input bind:value={name} onInput$={() => console.log(name.value)} />;
+});
+"#;
+        let code_of = |mode: Target| -> String {
+            transform_inputs(vec![("app.jsx", code)], mode)
+                .modules
+                .into_iter()
+                .map(|m| m.code)
+                .collect()
+        };
+
+        let dev = code_of(Target::Dev);
+        assert!(dev.contains("import { componentQrl, qrlDEV } from \"@qwik.dev/core\";"));
+        assert!(dev.contains("file: \"app.jsx\",\n\tlo: 86,\n\thi: 208,"));
+        assert!(dev.contains("displayName: \"app.jsx_App_component\""));
+        assert!(dev.contains("displayName: \"app.jsx_App_component_input_onInput\""));
+        assert!(dev.contains("inlinedQrlDEV(_val, \"_val\", {"));
+        assert!(dev.contains("displayName: \"app.jsx_App_component_input_bind_value\""));
+        assert!(dev.contains("}, [name])"));
+        assert!(!dev.contains("qrl("));
+
+        let test = code_of(Target::Test);
+        assert!(!test.contains("DEV"));
+        assert!(test.contains("inlinedQrl(_val, \"_val\", [name])"));
+    }
+
+    /// Asserts that check mode generates no code for the root module or any of its segments.
+    fn assert_no_code(options: TransformModulesOptions) {
+        let checked = process_modules(options, true);
+        assert!(checked.modules.iter().any(|m| m.segment.is_some()));
+        assert!(checked.modules.iter().all(|m| m.code.is_empty()));
+    }
+
+    #[test]
+    fn test_check_modules_reports_without_codegen() {
+        let code = r#"
+import { $, component$ } from '@qwik.dev/core';
+
+export const App = component$(() => {
+    function hola() {}
+    return $(() => hola());
+});
+"#;
+        let transformed = transform_inputs(vec![("app.js", code)], Target::Test);
+        let checked = check_modules(options_for(vec![("app.js", code)], Target::Test)).unwrap();
+
+        let transformed_segments: Vec<SegmentAnalysis> = transformed
+            .modules
+            .into_iter()
+            .filter_map(|m| m.segment)
+            .collect();
+        assert_eq!(checked.segments, transformed_segments);
+        assert_no_code(options_for(vec![("app.js", code)], Target::Test));
+        assert_eq!(checked.diagnostics.len(), 1);
+        assert_eq!(
+            checked.diagnostics[0].message,
+            transformed.diagnostics[0].message
+        );
+    }
+
+    #[test]
+    fn test_check_modules_reports_same_diagnostics() {
+        let code = r#"
+import { $, component$, useSignal } from '@qwik.dev/core';
+
+export const App = component$((props: { label: string }) => {
+    const count = useSignal<number>(0);
+    function hola(): void {}
+    const onClick = $(() => hola());
+    return (
+        <button class="a" className={props.label} onClick$={() => count.value++}>
+            {count.value as number}
+        </button>
+    );
+});
+"#;
+        let options = || TransformModulesOptions {
+            transpile_ts: true,
+            ..options_for(vec![("app.tsx", code)], Target::Test)
+        };
+        let transformed = transform_modules(options()).unwrap();
+        let checked = check_modules(options()).unwrap();
+
+        assert_eq!(transformed.diagnostics.len(), 2);
+        assert_eq!(
+            format!("{:?}", checked.diagnostics),
+            format!("{:?}", transformed.diagnostics)
+        );
+        assert_no_code(options());
+    }
+
     #[test]
     fn test_project_1() {
         // This should be a macro eventually
@@ -478,6 +936,8 @@ mod tests {
             strip_event_handlers: false,
             reg_ctx_name: None,
             is_server: None,
+            jsx_inspector: None,
+            panic_on_path: None,
         })
         .unwrap();
 
@@ -12,8 +12,10 @@ mod dead_code;
 mod entry_strategy;
 mod illegal_code;
 mod import_clean_up;
+mod inlined_fn;
 pub mod js_lib_interface;
 mod processing_failure;
+mod props_destructuring;
 mod ref_counter;
 mod segment;
 pub mod transform;
@@ -47,7 +47,10 @@ macro_rules! snapshot_res {
 
 #[macro_export]
 macro_rules! _assert_valid_transform {
-    ($debug:literal, $entry_strategy:expr) => {{
+    ($debug:literal, $entry_strategy:expr, $mode:expr) => {{
+        _assert_valid_transform!($debug, $entry_strategy, $mode, None, false);
+    }};
+    ($debug:literal, $entry_strategy:expr, $mode:expr, $strip_ctx_name:expr, $strip_event_handlers:expr) => {{
         let func_name = function_name!();
         let mut path = PathBuf::from("./src/test_input").join(format!("{func_name}.tsx"));
         let mut transpile_ts = true;
@@ -75,15 +78,17 @@ macro_rules! _assert_valid_transform {
             transpile_jsx: true,
             preserve_filenames: false,
             explicit_extensions: false,
-            mode: Target::Test,
+            mode: $mode,
             scope: None,
 
             core_module: None,
             strip_exports: None,
-            strip_ctx_name: None,
-            strip_event_handlers: false,
+            strip_ctx_name: $strip_ctx_name,
+            strip_event_handlers: $strip_event_handlers,
             reg_ctx_name: None,
             is_server: None,
+            jsx_inspector: None,
+            panic_on_path: None,
         };
 
         let result = transform_modules(options);
@@ -99,14 +104,31 @@ macro_rules! _assert_valid_transform {
 #[macro_export]
 macro_rules! assert_valid_transform {
     ($options:expr) => {{
-        _assert_valid_transform!(false, $options);
+        _assert_valid_transform!(false, $options, Target::Test);
+    }};
+}
+
+/// Like [assert_valid_transform], but builds for [Target::Dev].
+#[macro_export]
+macro_rules! assert_valid_transform_dev {
+    ($options:expr) => {{
+        _assert_valid_transform!(false, $options, Target::Dev);
+    }};
+}
+
+/// Like [assert_valid_transform], but strips the segments of the given markers and of event
+/// handlers.
+#[macro_export]
+macro_rules! assert_valid_transform_stripped {
+    ($options:expr, $strip_ctx_name:expr) => {{
+        _assert_valid_transform!(false, $options, Target::Test, Some($strip_ctx_name), true);
     }};
 }
 
 #[macro_export]
 macro_rules! assert_valid_transform_debug {
     ($options:expr) => {{
-        _assert_valid_transform!(true, $options);
+        _assert_valid_transform!(true, $options, Target::Test);
     }};
 }
 
@@ -1,12 +1,68 @@
-use crate::illegal_code::IllegalCodeType;
+use crate::illegal_code::{IllegalCodeType, QrlArgumentType, UnsafeCaptureType};
+use oxc_diagnostics::OxcDiagnostic;
+use oxc_span::{Span, SPAN};
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
 pub enum ProcessingFailure {
-    IllegalCode(IllegalCodeType),
+    /// The source could not be parsed, so no output is produced for it.
+    Parse(String, Span),
+    Semantic(String, Span),
+    IllegalCode(IllegalCodeType, Span),
+    UnsafeCapture(UnsafeCaptureType, Span),
+    InvalidQrlArgument(QrlArgumentType, Span),
+    /// An intrinsic element sets both `class` and `className`, so one of them is dropped.
+    DuplicateClass(Span),
+    /// The optimizer reached a state it does not expect for this code.
+    Internal(String, Span),
 }
 
-impl From<&IllegalCodeType> for ProcessingFailure {
-    fn from(value: &IllegalCodeType) -> Self {
-        ProcessingFailure::IllegalCode(value.clone())
+impl ProcessingFailure {
+    pub(crate) fn parse(diagnostic: &OxcDiagnostic) -> Self {
+        ProcessingFailure::Parse(
+            diagnostic.message.to_string(),
+            Self::primary_span(diagnostic),
+        )
+    }
+
+    pub(crate) fn semantic(diagnostic: &OxcDiagnostic) -> Self {
+        ProcessingFailure::Semantic(
+            diagnostic.message.to_string(),
+            Self::primary_span(diagnostic),
+        )
+    }
+
+    fn primary_span(diagnostic: &OxcDiagnostic) -> Span {
+        diagnostic
+            .labels
+            .as_ref()
+            .and_then(|labels| labels.first())
+            .map_or(SPAN, |label| {
+                Span::sized(label.offset() as u32, label.len() as u32)
+            })
+    }
+
+    pub(crate) fn span(&self) -> Span {
+        match self {
+            ProcessingFailure::Parse(_, span) => *span,
+            ProcessingFailure::Semantic(_, span) => *span,
+            ProcessingFailure::IllegalCode(_, span) => *span,
+            ProcessingFailure::UnsafeCapture(_, span) => *span,
+            ProcessingFailure::InvalidQrlArgument(_, span) => *span,
+            ProcessingFailure::DuplicateClass(span) => *span,
+            ProcessingFailure::Internal(_, span) => *span,
+        }
+    }
+
+    /// Whether this failure makes the affected code unusable, as opposed to merely suspicious.
+    pub(crate) fn is_error(&self) -> bool {
+        match self {
+            ProcessingFailure::Parse(_, _) => true,
+            ProcessingFailure::Semantic(_, _) => true,
+            ProcessingFailure::IllegalCode(_, _) => true,
+            ProcessingFailure::UnsafeCapture(capture, _) => capture.is_error(),
+            ProcessingFailure::InvalidQrlArgument(argument, _) => argument.is_error(),
+            ProcessingFailure::DuplicateClass(_) => false,
+            ProcessingFailure::Internal(_, _) => true,
+        }
     }
 }
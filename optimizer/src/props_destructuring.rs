@@ -0,0 +1,271 @@
+use oxc_allocator::CloneIn;
+use oxc_ast::ast::*;
+use oxc_ast_visit::{walk_mut, Visit, VisitMut};
+use oxc_semantic::{ReferenceFlags, ScopeId, SymbolFlags, SymbolId};
+use oxc_span::{Atom, SPAN};
+use oxc_syntax::identifier::is_identifier_name;
+use oxc_syntax::operator::LogicalOperator;
+use oxc_traverse::{BoundIdentifier, TraverseCtx};
+use std::collections::HashMap;
+
+use crate::component::REST_PROPS_NAME;
+
+/// Name of the parameter that replaces a destructured props pattern.
+const PROPS_PARAM: &str = "props";
+
+/// Rewrites the destructured props parameter of a component closure into reads of a single
+/// `props` parameter, so that each prop is only read where it is used and stays reactive.
+///
+/// - `({ title }) => title` becomes `(props) => props.title`.
+/// - A default value is applied at every read: `({ size = 1 })` reads `props.size ?? 1`.
+/// - A rest element becomes `const rest = _restProps(props, ["title"])` at the top of the body.
+///
+/// Patterns with computed keys or nested destructuring are left untouched, as are closures that
+/// assign to a destructured binding or already use the name `props`. Returns whether the body
+/// now calls `_restProps`.
+pub(crate) fn destructure_props<'a>(
+    params: &mut FormalParameters<'a>,
+    body: &mut FunctionBody<'a>,
+    is_expression_body: &mut bool,
+    scope_id: ScopeId,
+    ctx: &mut TraverseCtx<'a, ()>,
+) -> bool {
+    let [param] = params.items.as_mut_slice() else {
+        return false;
+    };
+    // A `props` parameter would shadow, or be shadowed by, another `props` binding.
+    if params.rest.is_some() || uses_name(param, body, PROPS_PARAM) {
+        return false;
+    }
+    let BindingPatternKind::ObjectPattern(pattern) = &mut param.pattern.kind else {
+        return false;
+    };
+    if !pattern.properties.iter().all(is_simple_property) {
+        return false;
+    }
+    let rest = match &pattern.rest {
+        Some(rest) => match &rest.argument.kind {
+            BindingPatternKind::BindingIdentifier(id) => Some((id.name, id.symbol_id())),
+            _ => return false,
+        },
+        None => None,
+    };
+    // A prop read cannot be assigned to.
+    let is_mutated = pattern
+        .properties
+        .iter()
+        .filter_map(property_symbol)
+        .chain(rest.map(|(_, symbol_id)| symbol_id))
+        .any(|symbol_id| ctx.scoping().symbol_is_mutated(symbol_id));
+    if is_mutated {
+        return false;
+    }
+
+    let builder = ctx.ast;
+    let mut reads = HashMap::new();
+    let mut keys = Vec::new();
+    for property in pattern.properties.iter_mut() {
+        let Some(key) = property.key.static_name() else {
+            continue;
+        };
+        let key = builder.atom(&key);
+        keys.push(key);
+        match &mut property.value.kind {
+            BindingPatternKind::BindingIdentifier(id) => {
+                reads.insert(id.symbol_id(), (key, None));
+            }
+            BindingPatternKind::AssignmentPattern(assignment) => {
+                if let BindingPatternKind::BindingIdentifier(id) = &assignment.left.kind {
+                    let symbol_id = id.symbol_id();
+                    let default = std::mem::replace(
+                        &mut assignment.right,
+                        builder.expression_null_literal(SPAN),
+                    );
+                    reads.insert(symbol_id, (key, Some(default)));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let props = ctx.generate_binding(
+        Atom::from(PROPS_PARAM),
+        scope_id,
+        SymbolFlags::FunctionScopedVariable,
+    );
+    param.pattern = props.create_binding_pattern(ctx);
+
+    PropsReads {
+        props: &props,
+        reads,
+        ctx,
+    }
+    .visit_function_body(body);
+
+    let Some((name, symbol_id)) = rest else {
+        return false;
+    };
+    let elements = builder.vec_from_iter(keys.into_iter().map(|key| {
+        ArrayExpressionElement::from(builder.expression_string_literal(SPAN, key, None))
+    }));
+    let arguments = builder.vec_from_array([
+        Argument::from(props.create_read_expression(ctx)),
+        Argument::from(builder.expression_array(SPAN, elements)),
+    ]);
+    let init = builder.expression_call(
+        SPAN,
+        ctx.create_unbound_ident_expr(SPAN, Atom::from(REST_PROPS_NAME), ReferenceFlags::Read),
+        None::<TSTypeParameterInstantiation<'a>>,
+        arguments,
+        false,
+    );
+    let declarator = builder.variable_declarator(
+        SPAN,
+        VariableDeclarationKind::Const,
+        builder.binding_pattern(
+            builder.binding_pattern_kind_binding_identifier_with_symbol_id(SPAN, name, symbol_id),
+            None::<TSTypeAnnotation<'a>>,
+            false,
+        ),
+        Some(init),
+        false,
+    );
+    let declaration = Statement::VariableDeclaration(builder.alloc_variable_declaration(
+        SPAN,
+        VariableDeclarationKind::Const,
+        builder.vec1(declarator),
+        false,
+    ));
+
+    if *is_expression_body {
+        // `(props) => expr` needs a block body to hold the declaration.
+        *is_expression_body = false;
+        if let Some(Statement::ExpressionStatement(statement)) = body.statements.pop() {
+            let argument = statement.unbox().expression;
+            body.statements
+                .push(builder.statement_return(SPAN, Some(argument)));
+        }
+    }
+    body.statements.insert(0, declaration);
+    true
+}
+
+fn is_simple_property(property: &BindingProperty<'_>) -> bool {
+    if property.computed || property.key.static_name().is_none() {
+        return false;
+    }
+    match &property.value.kind {
+        BindingPatternKind::BindingIdentifier(_) => true,
+        BindingPatternKind::AssignmentPattern(assignment) => {
+            matches!(
+                assignment.left.kind,
+                BindingPatternKind::BindingIdentifier(_)
+            )
+        }
+        _ => false,
+    }
+}
+
+fn property_symbol(property: &BindingProperty<'_>) -> Option<SymbolId> {
+    match &property.value.kind {
+        BindingPatternKind::BindingIdentifier(id) => Some(id.symbol_id()),
+        BindingPatternKind::AssignmentPattern(assignment) => match &assignment.left.kind {
+            BindingPatternKind::BindingIdentifier(id) => Some(id.symbol_id()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Whether `name` is bound or referenced anywhere in the parameter or body of a closure.
+fn uses_name(param: &FormalParameter<'_>, body: &FunctionBody<'_>, name: &str) -> bool {
+    let mut names = NameUses { name, found: false };
+    names.visit_formal_parameter(param);
+    names.visit_function_body(body);
+    names.found
+}
+
+struct NameUses<'n> {
+    name: &'n str,
+    found: bool,
+}
+
+impl<'a> Visit<'a> for NameUses<'_> {
+    fn visit_binding_identifier(&mut self, id: &BindingIdentifier<'a>) {
+        self.found |= id.name == self.name;
+    }
+
+    fn visit_identifier_reference(&mut self, id: &IdentifierReference<'a>) {
+        self.found |= id.name == self.name;
+    }
+}
+
+/// Replaces reads of destructured props with reads of the `props` parameter.
+struct PropsReads<'a, 'b, 'c> {
+    props: &'b BoundIdentifier<'a>,
+    /// The prop key and default value of each destructured binding.
+    reads: HashMap<SymbolId, (Atom<'a>, Option<Expression<'a>>)>,
+    ctx: &'c mut TraverseCtx<'a, ()>,
+}
+
+impl<'a> PropsReads<'a, '_, '_> {
+    fn prop_symbol(&self, id: &IdentifierReference<'a>) -> Option<SymbolId> {
+        let symbol_id = self
+            .ctx
+            .scoping()
+            .get_reference(id.reference_id())
+            .symbol_id()?;
+        self.reads.contains_key(&symbol_id).then_some(symbol_id)
+    }
+
+    fn prop_read(&mut self, id: &IdentifierReference<'a>) -> Option<Expression<'a>> {
+        let (key, default) = self.reads.get(&self.prop_symbol(id)?)?;
+        let (key, default) = (
+            *key,
+            default
+                .as_ref()
+                .map(|default| default.clone_in_with_semantic_ids(self.ctx.ast.allocator)),
+        );
+        let builder = self.ctx.ast;
+        let props = self.props.create_read_expression(self.ctx);
+        let read = Expression::from(if is_identifier_name(&key) {
+            builder.member_expression_static(
+                id.span,
+                props,
+                builder.identifier_name(SPAN, key),
+                false,
+            )
+        } else {
+            let key = builder.expression_string_literal(SPAN, key, None);
+            builder.member_expression_computed(id.span, props, key, false)
+        });
+        Some(match default {
+            Some(default) => {
+                builder.expression_logical(id.span, read, LogicalOperator::Coalesce, default)
+            }
+            None => read,
+        })
+    }
+}
+
+impl<'a> VisitMut<'a> for PropsReads<'a, '_, '_> {
+    fn visit_expression(&mut self, expr: &mut Expression<'a>) {
+        if let Expression::Identifier(id) = expr {
+            if let Some(read) = self.prop_read(id) {
+                *expr = read;
+                return;
+            }
+        }
+        walk_mut::walk_expression(self, expr);
+    }
+
+    fn visit_object_property(&mut self, property: &mut ObjectProperty<'a>) {
+        // `{ title }` has to be spelled out as `{ title: props.title }`.
+        if property.shorthand {
+            if let Expression::Identifier(id) = &property.value {
+                property.shorthand = self.prop_symbol(id).is_none();
+            }
+        }
+        walk_mut::walk_object_property(self, property);
+    }
+}
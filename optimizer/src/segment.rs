@@ -112,6 +112,10 @@ enum SegmentName {
 
 impl SegmentName {
     fn new(name0: String) -> Self {
+        // `sync$` functions are serialized in place rather than extracted.
+        if name0 == SYNC_MARKER {
+            return SegmentName::Name(name0);
+        }
         let name = name0.strip_suffix(MARKER_SUFFIX);
 
         match name {
@@ -263,4 +267,13 @@ mod tests {
         let segment = builder.new_segment("bar", &segments);
         assert_eq!(segment, Segment::Named("bar".to_string()));
     }
+
+    #[test]
+    fn test_sync_is_not_a_qrl() {
+        let mut builder = SegmentBuilder::new();
+        let segments = vec![Segment::Named("foo".to_string())];
+        let segment = builder.new_segment("sync$", &segments);
+        assert_eq!(segment, Segment::Named("sync$".to_string()));
+        assert!(!segment.is_qrl());
+    }
 }
@@ -0,0 +1,24 @@
+import { component$, $ } from '@qwik.dev/core';
+
+export const PREFIX = 'item';
+export const DOUBLE = PREFIX + PREFIX;
+const SEPARATOR = '-';
+let counter = 0;
+function format(value) {
+    return `${PREFIX}${SEPARATOR}${value}`;
+}
+class Store {
+    items = [];
+}
+export function exported() {
+    return format(counter);
+}
+const unused = 'unused';
+const localFormat = format(SEPARATOR);
+
+export const App = component$(() => {
+    const store = new Store();
+    store.items.push(SEPARATOR);
+    const onClick = $(() => console.log(format(counter), exported()));
+    return <button onClick$={onClick}>{format(PREFIX)}</button>;
+});
@@ -0,0 +1,14 @@
+import { component$, useSignal } from '@qwik.dev/core';
+import { Field } from './field';
+
+export const Form = component$(() => {
+    const name = useSignal('');
+    const agreed = useSignal(false);
+    return (
+        <form>
+            <input bind:value={name} onInput$={() => console.log('typed')} />
+            <input type="checkbox" bind:checked={agreed} />
+            <Field bind:value={name} />
+        </form>
+    );
+});
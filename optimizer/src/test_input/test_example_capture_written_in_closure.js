@@ -0,0 +1,12 @@
+import { component$, useTask$ } from '@qwik.dev/core';
+
+export const App = component$(() => {
+    let count = 0;
+
+    useTask$(() => {
+        count++;
+        console.log(count);
+    });
+
+    return <p>{count}</p>;
+});
@@ -0,0 +1,15 @@
+import { component$, useSignal } from '@qwik.dev/core';
+
+export const App = component$((props) => {
+    const active = useSignal(false);
+    return (
+        <div className="container">
+            <p className={props.cls}>props</p>
+            <p class={['a', 'b', { c: true }]}>array</p>
+            <p class={{ active: true, hidden: false }}>object</p>
+            <p class={['a', { active: active.value }]}>reactive</p>
+            <p class="one" className="two">both</p>
+            <Child className="kept" />
+        </div>
+    );
+});
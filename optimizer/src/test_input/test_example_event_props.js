@@ -0,0 +1,18 @@
+import { component$, useSignal } from '@qwik.dev/core';
+import { Button } from './button';
+
+export const Events = component$(() => {
+    const count = useSignal(0);
+    return (
+        <div
+            onClick$={() => count.value++}
+            on-myEvent$={() => console.log('custom')}
+            document:onScroll$={() => console.log('scroll')}
+            window:onResize$={() => console.log('resize')}
+            onDblClick$={() => console.log('double')}
+            onClick$={() => console.log('second')}
+        >
+            <Button onClick$={() => count.value--} />
+        </div>
+    );
+});
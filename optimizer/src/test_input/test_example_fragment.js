@@ -0,0 +1,18 @@
+import { component$, Fragment } from '@qwik.dev/core';
+
+export const List = component$((props) => {
+    return (
+        <>
+            <h1>Items</h1>
+            {props.items.map((item) => (
+                <Fragment key={item.id}>
+                    <dt>{item.name}</dt>
+                    <dd>{item.description}</dd>
+                </Fragment>
+            ))}
+            <>
+                <p>Static</p>
+            </>
+        </>
+    );
+});
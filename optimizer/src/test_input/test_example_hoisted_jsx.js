@@ -0,0 +1,14 @@
+import { component$, useSignal } from '@qwik.dev/core';
+
+export const Card = component$(() => {
+    const count = useSignal(0);
+    return (
+        <section class="card">
+            <header class="title"><h2>Static title</h2></header>
+            <p class="title">{count.value}</p>
+            <footer>
+                <a href="/about">About</a>
+            </footer>
+        </section>
+    );
+});
@@ -0,0 +1,11 @@
+import { $, component$ } from '@qwik.dev/core';
+
+export const App = component$(() => {
+    function hola() {}
+    const valid = $(() => console.log('valid'));
+    return (
+        <button onClick$={() => hola()} onFocus$={valid}>
+            {$(() => hola())}
+        </button>
+    );
+});
@@ -0,0 +1,28 @@
+import { component$, useSignal } from '@qwik.dev/core';
+import { getHandler, getTitle } from './utils';
+
+export const Flags = component$((props) => {
+    const selected = useSignal('');
+    const items = ['a', 'b'];
+    return (
+        <main>
+            <p class="static">Static</p>
+            <p title={getTitle()}>Dynamic prop</p>
+            <button onClick$={getHandler()}>Dynamic listener</button>
+            <section>
+                <span>{getTitle()}</span>
+            </section>
+            <ul>
+                {items.map((item) => (
+                    <li onClick$={() => (selected.value = item)}>{item}</li>
+                ))}
+            </ul>
+            <ol>
+                {items.map((item) => (
+                    <li render$={() => item}>{item}</li>
+                ))}
+            </ol>
+            <div {...props} onClick$={() => console.log('spread')} />
+        </main>
+    );
+});
@@ -0,0 +1,15 @@
+import { component$ } from '@qwik.dev/core';
+
+export const Split = component$((props) => {
+    const a = { title: 'a' };
+    const b = { title: 'b' };
+    return (
+        <section>
+            <div {...a} x="1" {...b} />
+            <div id="first" {...a} class="last" onClick$={() => console.log('clicked')} />
+            <div key="explicit" {...props.attrs} />
+            <div {...a} key="wins" />
+            <div {...getProps()} />
+        </section>
+    );
+});
@@ -0,0 +1,18 @@
+import { component$ } from '@qwik.dev/core';
+
+export const Text = component$(() => {
+    return (
+        <article>
+            <p>
+                <b>bold</b> <i>italic</i>
+            </p>
+            <p>
+                This text
+                spans   several
+                lines.
+            </p>
+            <p>Tom&nbsp;&amp;&nbsp;Jerry &copy; &#169; &#xA9;</p>
+            <p>{'{'}braces{'}'} &lt;tag&gt;</p>
+        </article>
+    );
+});
@@ -0,0 +1,32 @@
+import { component$, useSignal } from '@qwik.dev/core';
+
+export const List = component$(() => {
+    const selected = useSignal('');
+    const items = ['a', 'b'];
+    const rows = [];
+    for (const row of [1, 2]) {
+        rows.push(<p onClick$={(ev) => console.log(ev, row)}>{row}</p>);
+    }
+    return (
+        <ul>
+            {rows}
+            {items.map((item, index) => (
+                <li
+                    onClick$={() => (selected.value = item)}
+                    onDblClick$={(_, el) => console.log(el, index, item)}
+                >
+                    {item}
+                </li>
+            ))}
+            {items.map((item) => (
+                <Item onSelect$={() => (selected.value = item)} />
+            ))}
+        </ul>
+    );
+});
+
+export const Nested = component$(() => {
+    const selected = useSignal('');
+    const render = (label) => <button onClick$={() => (selected.value = label)}>{label}</button>;
+    return <div>{render('a')}</div>;
+});
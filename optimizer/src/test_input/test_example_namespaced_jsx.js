@@ -0,0 +1,10 @@
+import { $, component$ } from '@qwik.dev/core';
+
+export const Icon = component$(() => {
+    return (
+        <svg xmlns:xlink="http://www.w3.org/1999/xlink" aria-hidden="true">
+            <svg:use xlink:href="#icon" />
+            <div q:slot="label" document:onScroll={$(() => console.log('scroll'))}>icon</div>
+        </svg>
+    );
+});
@@ -0,0 +1,26 @@
+import { component$ } from '@qwik.dev/core';
+
+export const Card = component$(({ title, size = 'md', 'aria-label': label, ...rest }) => {
+    const heading = { title, size, label };
+    return (
+        <div {...rest} aria-label={label} data-size={size}>
+            <h2>{title}</h2>
+            <pre>{JSON.stringify(heading)}</pre>
+        </div>
+    );
+});
+
+export const Badge = component$(({ count }) => <span>{count}</span>);
+
+export const Nested = component$(({ user: { name } }) => <span>{name}</span>);
+
+export const Shadowed = component$(({ label }) => {
+    const props = { label };
+    return <span {...props}>{label}</span>;
+});
+
+export const Mutated = component$(({ title, count }) => {
+    title = title.trim();
+    count++;
+    return <h1>{title} {count}</h1>;
+});
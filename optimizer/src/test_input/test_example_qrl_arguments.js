@@ -0,0 +1,19 @@
+import { $, component$, useTask$, useStyles$ } from '@qwik.dev/core';
+import { Counter } from './counter';
+import styles from './styles.css?inline';
+
+export const handler = () => console.log('exported');
+
+export const Imported = component$(Counter);
+
+export const App = component$(() => {
+    useStyles$(styles);
+    const track = () => console.log('local');
+    useTask$(track);
+    return $(handler);
+});
+
+export const Literal = $('not a function');
+
+const fns = [() => console.log('spread')];
+export const Spread = $(...fns);
@@ -0,0 +1,17 @@
+import { component$, useSignal, useStore } from '@qwik.dev/core';
+
+const PREFIX = 'Hello';
+
+export const Greeting = component$((props) => {
+    const store = useStore({ name: 'Qwik', count: 0 });
+    const sig = useSignal('');
+    return (
+        <div title={store.name} class={props.class} data-count={store.count + 1}>
+            <input value={sig.value} />
+            <Child label={props.label} style={{ color: store.color }} />
+            {PREFIX} {store.name}
+            {`${PREFIX} ${store.name}!`}
+            {store.items.map((item) => item)}
+        </div>
+    );
+});
@@ -0,0 +1,16 @@
+import { component$, useSignal, useStore } from '@qwik.dev/core';
+
+export const App = component$(() => {
+    const input = useSignal();
+    const store = useStore({ el: undefined });
+    const items = [];
+    return (
+        <div>
+            <input ref={input} />
+            <textarea ref={store.el} />
+            <canvas ref={(el) => items.push(el)} />
+            <span ref={(el) => console.log(el)} class="static" />
+            <Child ref={input} />
+        </div>
+    );
+});
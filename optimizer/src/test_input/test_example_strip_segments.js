@@ -0,0 +1,12 @@
+import { component$, server$, useSignal } from '@qwik.dev/core';
+
+export const Counter = component$(() => {
+    const count = useSignal(0);
+    const step = 2;
+    const save = server$(() => db.save(count.value, step));
+    return (
+        <button onClick$={() => (count.value += step)} onDblClick$={() => save()}>
+            {count.value}
+        </button>
+    );
+});
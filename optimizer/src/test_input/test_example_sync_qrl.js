@@ -0,0 +1,24 @@
+import { $, component$, sync$ } from '@qwik.dev/core';
+
+export const Form = component$(() => {
+    return (
+        <form
+            preventdefault:submit
+            onSubmit$={[
+                sync$((event, target) => {
+                    if (target.getAttribute('data-skip') === 'true') {
+                        event.preventDefault();
+                    }
+                }),
+                $(() => console.log('submitted')),
+            ]}
+        >
+            <a onClick$={sync$(function (e) { e.preventDefault(); })}>link</a>
+        </form>
+    );
+});
+
+export const Outer = component$(() => {
+    const attribute = 'data-skip';
+    return <a onClick$={sync$((e) => e.target.hasAttribute(attribute))}>outer</a>;
+});
@@ -0,0 +1,20 @@
+import { component$, useSignal } from '@qwik.dev/core';
+
+export const Page = component$(() => {
+    const title = useSignal('Home');
+    const css = '.page { color: red; }';
+    return (
+        <div>
+            <title>{title.value}</title>
+            <textarea>
+                Initial
+                text
+            </textarea>
+            <option>Count: {title.value} `items`</option>
+            <style dangerouslySetInnerHTML={css} />
+            <script dangerouslySetInnerHTML={title.value} />
+            <span>{title.value}</span>
+            <div dangerouslySetInnerHTML={title.value} />
+        </div>
+    );
+});
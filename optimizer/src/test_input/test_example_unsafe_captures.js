@@ -0,0 +1,49 @@
+import { $, component$, useTask$ } from '@qwik.dev/core';
+
+class Counter {}
+
+function* ticks() {
+    yield 1;
+}
+
+export const App = component$(function () {
+    const counter = new Counter();
+    const created = new Date();
+    const iter = ticks();
+    const entries = new Map().entries();
+    let count = 0;
+    let label = 'a';
+
+    useTask$(() => {
+        console.log(this, arguments, counter, created, iter, entries, count, label);
+    });
+
+    count++;
+
+    return $(() => {
+        label = 'b';
+        return function () {
+            return this;
+        };
+    });
+});
+
+export const Param = component$((props) => {
+    useTask$(() => {
+        console.log(props);
+    });
+    props = {};
+    return null;
+});
+
+export const Hoisted = component$(() => {
+    const later = laterTicks();
+    useTask$(() => {
+        console.log(later);
+    });
+    return null;
+});
+
+function* laterTicks() {
+    yield 1;
+}
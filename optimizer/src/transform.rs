@@ -13,7 +13,7 @@ use oxc_allocator::{
 };
 use oxc_ast::ast::*;
 use oxc_ast::{match_member_expression, AstBuilder, AstType, Comment, CommentKind};
-use oxc_ast_visit::{Visit, VisitMut};
+use oxc_ast_visit::{walk, Visit, VisitMut};
 use oxc_codegen::{Codegen, CodegenOptions, Context, Gen};
 use oxc_index::Idx;
 use oxc_transformer::JsxOptions;
@@ -22,16 +22,18 @@ use std::collections::{BTreeSet, HashMap, HashSet};
 
 use crate::component::*;
 use crate::import_clean_up::ImportCleanUp;
+use crate::inlined_fn::convert_reactive_expression;
 use crate::macros::*;
+use crate::props_destructuring::destructure_props;
 use crate::source::Source;
 use oxc_parser::Parser;
 use oxc_semantic::{
-    NodeId, ReferenceId, ScopeFlags, Scoping, SemanticBuilder, SemanticBuilderReturn, SymbolFlags,
-    SymbolId,
+    NodeId, ReferenceFlags, ReferenceId, ScopeFlags, ScopeId, Scoping, SemanticBuilder,
+    SemanticBuilderReturn, SymbolFlags, SymbolId,
 };
 use oxc_span::*;
 use oxc_transformer::{TransformOptions as OxcTransformOptions, Transformer, TypeScriptOptions};
-use oxc_traverse::{traverse_mut, Ancestor, Traverse, TraverseCtx};
+use oxc_traverse::{traverse_mut, Ancestor, BoundIdentifier, Traverse, TraverseCtx};
 use serde::{Deserialize, Serialize};
 use std::cell::{Cell, RefCell};
 use std::fmt::{write, Display, Pointer};
@@ -50,7 +52,9 @@ pub struct OptimizedApp {
 }
 
 use crate::ext::*;
-use crate::illegal_code::{IllegalCode, IllegalCodeType};
+use crate::illegal_code::{
+    IllegalCode, IllegalCodeType, QrlArgumentType, UnsafeCapture, UnsafeCaptureType,
+};
 use crate::processing_failure::ProcessingFailure;
 
 impl OptimizedApp {
@@ -88,12 +92,13 @@ impl Display for OptimizedApp {
 }
 
 pub struct OptimizationResult {
-    pub optimized_app: OptimizedApp,
+    /// `None` when the source could not be parsed.
+    pub optimized_app: Option<OptimizedApp>,
     pub errors: Vec<ProcessingFailure>,
 }
 
 impl OptimizationResult {
-    pub fn new(optimized_app: OptimizedApp, errors: Vec<ProcessingFailure>) -> Self {
+    pub fn new(optimized_app: Option<OptimizedApp>, errors: Vec<ProcessingFailure>) -> Self {
         Self {
             optimized_app,
             errors,
@@ -101,6 +106,18 @@ impl OptimizationResult {
     }
 }
 
+/// Set when no event handler of the element can change between renders.
+const JSX_STATIC_LISTENERS_FLAG: u32 = 1 << 0;
+/// Set when neither the props nor the descendants of the element can change between renders.
+const JSX_STATIC_SUBTREE_FLAG: u32 = 1 << 1;
+/// Set when event handlers capture values that have to be moved onto the element.
+const JSX_MOVED_CAPTURES_FLAG: u32 = 1 << 2;
+
+/// Array methods whose callback runs once per element, like the body of a loop.
+const ITERATION_METHODS: [&str; 7] = [
+    "map", "flatMap", "forEach", "filter", "find", "some", "every",
+];
+
 struct JsxState<'gen> {
     is_fn: bool,
     is_text_only: bool,
@@ -108,12 +125,334 @@ struct JsxState<'gen> {
     should_runtime_sort: bool,
     static_listeners: bool,
     static_subtree: bool,
+    moved_captures: bool,
     key_prop: Option<Expression<'gen>>,
+    /// Spread props that come after the `key` prop, and so may override it.
+    key_spreads: Vec<Expression<'gen>>,
+    /// Bindings of an enclosing loop that the event handlers of the element take as parameters
+    /// after the event and the element, in order.
+    loop_params: Vec<SymbolId>,
+    /// Whether a `class` or `className` prop has been seen.
+    has_class: bool,
+    /// The key to use when no `key` prop is given.
+    auto_key: Option<String>,
     var_props: OxcVec<'gen, ObjectPropertyKind<'gen>>,
     const_props: OxcVec<'gen, ObjectPropertyKind<'gen>>,
     children: OxcVec<'gen, ArrayExpressionElement<'gen>>,
 }
 
+impl<'gen> JsxState<'gen> {
+    fn new(
+        is_fn: bool,
+        is_text_only: bool,
+        is_segment: bool,
+        auto_key: Option<String>,
+        allocator: &'gen Allocator,
+    ) -> Self {
+        Self {
+            is_fn,
+            is_text_only,
+            is_segment,
+            should_runtime_sort: false,
+            static_listeners: true,
+            static_subtree: true,
+            moved_captures: false,
+            key_prop: None,
+            key_spreads: Vec::new(),
+            loop_params: Vec::new(),
+            has_class: false,
+            auto_key,
+            var_props: OxcVec::new_in(allocator),
+            const_props: OxcVec::new_in(allocator),
+            children: OxcVec::new_in(allocator),
+        }
+    }
+
+    fn flags(&self) -> u32 {
+        let mut flags = 0;
+        if self.static_listeners {
+            flags |= JSX_STATIC_LISTENERS_FLAG;
+        }
+        if self.static_subtree {
+            flags |= JSX_STATIC_SUBTREE_FLAG;
+        }
+        if self.moved_captures {
+            flags |= JSX_MOVED_CAPTURES_FLAG;
+        }
+        flags
+    }
+
+    fn add_prop(
+        &mut self,
+        builder: &AstBuilder<'gen>,
+        span: Span,
+        key: PropertyKey<'gen>,
+        value: Expression<'gen>,
+        is_const: bool,
+    ) {
+        let props = if is_const {
+            &mut self.const_props
+        } else {
+            &mut self.var_props
+        };
+        props.push(builder.object_property_kind_object_property(
+            span,
+            PropertyKind::Init,
+            key,
+            value,
+            false,
+            false,
+            false,
+        ));
+    }
+
+    /// Adds an event handler, combining the handlers of an event that is listened to more than
+    /// once into a single array.
+    fn add_event_handler(
+        &mut self,
+        builder: &AstBuilder<'gen>,
+        span: Span,
+        event: &str,
+        handler: Expression<'gen>,
+        is_const: bool,
+    ) {
+        if !is_const {
+            self.static_listeners = false;
+        }
+        let handlers = find_prop_value(&mut self.const_props, event)
+            .or_else(|| find_prop_value(&mut self.var_props, event));
+        match handlers {
+            Some(handlers) => merge_handlers(builder, handlers, handler),
+            None => {
+                let key = string_key(builder, span, event);
+                self.add_prop(builder, span, key, handler, is_const);
+            }
+        }
+    }
+}
+
+/// Detects references to bindings that are declared in a loop below the enclosing `$` closure but
+/// outside of an event handler, such as the parameters of a `.map()` callback. The handler can
+/// only capture these by having them moved onto the element.
+struct MovedCaptures<'s> {
+    scoping: &'s Scoping,
+    handler_scope: ScopeId,
+    qrl_scope: ScopeId,
+    loop_scopes: &'s HashSet<ScopeId>,
+    /// The moved bindings, in the order they are first referenced.
+    found: Vec<SymbolId>,
+}
+
+impl<'a> Visit<'a> for MovedCaptures<'_> {
+    fn visit_identifier_reference(&mut self, id: &IdentifierReference<'a>) {
+        let Some(symbol_id) = self.scoping.get_reference(id.reference_id()).symbol_id() else {
+            return;
+        };
+        let symbol_scope_id = self.scoping.symbol_scope_id(symbol_id);
+        let mut in_loop = false;
+        let is_moved = symbol_scope_id != self.qrl_scope
+            && self
+                .scoping
+                .scope_ancestors(symbol_scope_id)
+                .take_while(|scope_id| *scope_id != self.handler_scope)
+                .any(|scope_id| {
+                    if scope_id == self.qrl_scope {
+                        return true;
+                    }
+                    in_loop |= self.loop_scopes.contains(&scope_id);
+                    false
+                });
+        if is_moved && in_loop && !self.found.contains(&symbol_id) {
+            self.found.push(symbol_id);
+        }
+    }
+}
+
+/// Collects the bindings that a segment captures from its enclosing scopes: those referenced in
+/// the segment that are neither module-level nor declared within the segment itself.
+struct SegmentCaptures<'s> {
+    scoping: &'s Scoping,
+    /// The scope of the segment's closure, if it is one.
+    segment_scope: Option<ScopeId>,
+    /// The captured bindings, in the order they are first referenced.
+    found: Vec<SymbolId>,
+}
+
+impl<'a> Visit<'a> for SegmentCaptures<'_> {
+    fn visit_identifier_reference(&mut self, id: &IdentifierReference<'a>) {
+        let Some(symbol_id) = self.scoping.get_reference(id.reference_id()).symbol_id() else {
+            return;
+        };
+        let symbol_scope_id = self.scoping.symbol_scope_id(symbol_id);
+        let is_captured = symbol_scope_id != self.scoping.root_scope_id()
+            && self.segment_scope.is_none_or(|segment_scope| {
+                !self
+                    .scoping
+                    .scope_ancestors(symbol_scope_id)
+                    .any(|scope_id| scope_id == segment_scope)
+            });
+        if is_captured && !self.found.contains(&symbol_id) {
+            self.found.push(symbol_id);
+        }
+    }
+}
+
+/// Collects the references in a `sync$` function to bindings declared outside of it, which are not
+/// shipped along with its source. Only globals and the function's own bindings can be used.
+struct SyncReferences<'s> {
+    scoping: &'s Scoping,
+    /// The scope of the `sync$` function.
+    function_scope: ScopeId,
+    /// The names and spans of the offending references.
+    found: Vec<(String, Span)>,
+}
+
+impl<'a> Visit<'a> for SyncReferences<'_> {
+    fn visit_identifier_reference(&mut self, id: &IdentifierReference<'a>) {
+        let Some(symbol_id) = self.scoping.get_reference(id.reference_id()).symbol_id() else {
+            return;
+        };
+        let is_own = self
+            .scoping
+            .scope_ancestors(self.scoping.symbol_scope_id(symbol_id))
+            .any(|scope_id| scope_id == self.function_scope);
+        if !is_own {
+            self.found.push((id.name.to_string(), id.span));
+        }
+    }
+}
+
+/// Collects the generator functions declared in a program. Declarations are hoisted, so a generator
+/// can be called before the traversal reaches its declaration.
+#[derive(Default)]
+struct GeneratorDeclarations {
+    found: HashSet<SymbolId>,
+}
+
+impl<'a> Visit<'a> for GeneratorDeclarations {
+    fn visit_function(&mut self, function: &Function<'a>, flags: ScopeFlags) {
+        if function.generator && function.is_declaration() {
+            if let Some(symbol_id) = function.id.as_ref().and_then(|id| id.symbol_id.get()) {
+                self.found.insert(symbol_id);
+            }
+        }
+        walk::walk_function(self, function, flags);
+    }
+}
+
+/// A `$` closure that is currently being traversed.
+struct QrlScope<'gen> {
+    scope_id: ScopeId,
+    /// The value of [TransformGenerator::function_depth] when the closure was entered.
+    function_depth: usize,
+    /// Arrow functions share `this` and `arguments` with their enclosing function, so referencing
+    /// either of them from an arrow closure is a capture.
+    captures_this: bool,
+    jsx_keys: JsxKeys,
+    /// Module-level declarations for the segment the closure is extracted into.
+    hoisted: Vec<Statement<'gen>>,
+    /// Hoisted const prop objects by their source, so that identical ones are shared.
+    hoisted_props: HashMap<String, BoundIdentifier<'gen>>,
+    /// Number of `_hoisted_<n>` constants declared so far.
+    hoisted_consts: usize,
+    /// Number of `_fnSignal` functions hoisted into `_hf<n>` constants so far.
+    hoisted_fns: usize,
+}
+
+impl<'gen> QrlScope<'gen> {
+    /// Declares `const <name> = <expr>` among the hoisted declarations of the segment, leaving a
+    /// reference to the constant in place of the expression.
+    fn hoist(
+        &mut self,
+        builder: &AstBuilder<'gen>,
+        name: Atom<'gen>,
+        expr: &mut Expression<'gen>,
+        ctx: &mut TraverseCtx<'gen, ()>,
+    ) -> BoundIdentifier<'gen> {
+        let root_scope_id = ctx.scoping().root_scope_id();
+        let symbol_id = ctx.scoping_mut().create_symbol(
+            SPAN,
+            &name,
+            SymbolFlags::ConstVariable,
+            root_scope_id,
+            NodeId::DUMMY,
+        );
+        let value = move_expression(builder, expr);
+        let declarator = builder.variable_declarator(
+            SPAN,
+            VariableDeclarationKind::Const,
+            builder.binding_pattern(
+                builder
+                    .binding_pattern_kind_binding_identifier_with_symbol_id(SPAN, name, symbol_id),
+                None::<OxcBox<TSTypeAnnotation<'gen>>>,
+                false,
+            ),
+            Some(value),
+            false,
+        );
+        self.hoisted.push(Statement::VariableDeclaration(
+            builder.alloc_variable_declaration(
+                SPAN,
+                VariableDeclarationKind::Const,
+                builder.vec1(declarator),
+                false,
+            ),
+        ));
+        let hoisted = BoundIdentifier::new(name, symbol_id);
+        *expr = hoisted.create_read_expression(ctx);
+        hoisted
+    }
+
+    /// Hoists the function and the source of a `_fnSignal(fn, [deps], "source")` call into
+    /// `_hf<n>` and `_hf<n>_str`, so that the function is created once rather than on every render.
+    fn hoist_fn_signal(
+        &mut self,
+        builder: &AstBuilder<'gen>,
+        expr: &mut Expression<'gen>,
+        ctx: &mut TraverseCtx<'gen, ()>,
+    ) {
+        let Expression::CallExpression(call) = expr else {
+            return;
+        };
+        let name = format!("{HOISTED_FN_PREFIX}{}", self.hoisted_fns);
+        self.hoisted_fns += 1;
+        if let Some(function) = call.arguments[0].as_expression_mut() {
+            self.hoist(builder, builder.atom(&name), function, ctx);
+        }
+        if let Some(source) = call.arguments[2].as_expression_mut() {
+            self.hoist(builder, builder.atom(&format!("{name}_str")), source, ctx);
+        }
+    }
+}
+
+/// Generates the keys of JSX elements that are not given an explicit `key` prop.
+///
+/// Keys are `<hash-prefix>_<n>`, where the prefix is taken from the hash of the enclosing segment
+/// and `n` counts the keyed elements of that segment, so that they stay stable across builds.
+struct JsxKeys {
+    prefix: String,
+    counter: u32,
+}
+
+impl JsxKeys {
+    fn new(id: &Id) -> Self {
+        Self {
+            prefix: id.hash.chars().take(2).collect(),
+            counter: 0,
+        }
+    }
+
+    /// Returns the key for an element being entered. Only components and the root elements that
+    /// the segment returns are keyed.
+    fn next_key(&mut self, is_fn: bool, is_root: bool) -> Option<String> {
+        (is_fn || is_root).then(|| {
+            let key = format!("{}_{}", self.prefix, self.counter);
+            self.counter += 1;
+            key
+        })
+    }
+}
+
 pub struct TransformGenerator<'gen> {
     pub options: TransformOptions,
 
@@ -133,25 +472,58 @@ pub struct TransformGenerator<'gen> {
 
     symbol_by_name: HashMap<String, SymbolId>,
 
-    component_stack: Vec<QrlComponent>,
-
-    qrl_stack: Vec<Qrl>,
-
     import_stack: Vec<BTreeSet<Import>>,
 
     const_stack: Vec<BTreeSet<SymbolId>>,
 
     import_by_symbol: HashMap<SymbolId, Import>,
 
+    /// Non-exported top-level bindings referenced from a segment, which the root module has to
+    /// export as `_auto_<name>` for the segment to import them.
+    auto_exports: Vec<String>,
+
     removed: HashMap<SymbolId, IllegalCodeType>,
 
+    qrl_scope_stack: Vec<QrlScope<'gen>>,
+
+    /// Declarations hoisted out of the `$` closure that was exited last, to be emitted in its
+    /// segment module.
+    hoisted: Vec<Statement<'gen>>,
+
+    /// Number of non-arrow functions entered so far; each one rebinds `this` and `arguments`.
+    function_depth: usize,
+
+    /// Bindings whose values can not be serialized should they be captured by a `$` closure.
+    unsafe_bindings: HashMap<SymbolId, UnsafeCaptureType>,
+
+    generators: HashSet<SymbolId>,
+
+    /// `let` bindings captured by `$` closures, with the scopes of the capturing closures; any
+    /// later write to them from outside those closures is reported.
+    captured_mutables: HashMap<SymbolId, Vec<ScopeId>>,
+
+    /// Scopes that run once per iteration: those of `for` loops and of array iteration callbacks.
+    loop_scopes: HashSet<ScopeId>,
+
+    /// The constants declared by [Self::hoist], whose values are static.
+    hoisted_symbols: HashSet<SymbolId>,
+
+    /// Spans of the segments that were replaced with a `_noopQrl` rather than emitted.
+    noop_segments: Vec<Span>,
+
     source_info: &'gen SourceInfo,
 
+    source_text: &'gen str,
+
+    /// Byte offset at which each line of [Self::source_text] starts.
+    line_starts: Vec<usize>,
+
     scope: Option<String>,
 
     jsx_stack: Vec<JsxState<'gen>>,
 
-    jsx_key_counter: u32,
+    /// Keys for JSX outside of any `$` closure.
+    module_jsx_keys: JsxKeys,
 
     /// Marks whether each JSX attribute in the stack is var (false) or const (true).
     /// An attribute is considered var if it:
@@ -169,12 +541,15 @@ pub struct TransformGenerator<'gen> {
 impl<'gen> TransformGenerator<'gen> {
     fn new(
         source_info: &'gen SourceInfo,
+        source_text: &'gen str,
         options: TransformOptions,
         scope: Option<String>,
         allocator: &'gen Allocator,
     ) -> Self {
         let qwik_core_import_path = PathBuf::from("@qwik/core");
         let builder = AstBuilder::new(allocator);
+        let module_jsx_keys =
+            JsxKeys::new(&Id::new(source_info, &Vec::new(), &options.target, &scope));
         Self {
             options,
             components: Vec::new(),
@@ -185,16 +560,26 @@ impl<'gen> TransformGenerator<'gen> {
             segment_stack: Vec::new(),
             segment_builder: SegmentBuilder::new(),
             symbol_by_name: Default::default(),
-            component_stack: Vec::new(),
-            qrl_stack: Vec::new(),
             import_stack: vec![BTreeSet::new()],
             const_stack: vec![BTreeSet::new()],
             import_by_symbol: HashMap::default(),
+            auto_exports: Vec::new(),
             removed: HashMap::new(),
+            qrl_scope_stack: Vec::new(),
+            hoisted: Vec::new(),
+            function_depth: 0,
+            unsafe_bindings: HashMap::new(),
+            generators: HashSet::new(),
+            captured_mutables: HashMap::new(),
+            loop_scopes: HashSet::new(),
+            hoisted_symbols: HashSet::new(),
+            noop_segments: Vec::new(),
             source_info,
+            source_text,
+            line_starts: line_starts(source_text),
             scope,
             jsx_stack: Vec::new(),
-            jsx_key_counter: 0,
+            module_jsx_keys,
             expr_is_const_stack: Vec::new(),
             replace_expr: None,
         }
@@ -249,6 +634,751 @@ impl<'gen> TransformGenerator<'gen> {
     fn new_segment<T: AsRef<str>>(&mut self, input: T) -> Segment {
         self.segment_builder.new_segment(input, &self.segment_stack)
     }
+
+    /// Whether the function currently being entered is the argument of a `$` marker call, or the
+    /// value of a `$` prop such as `onClick$`.
+    fn is_qrl_closure(ctx: &TraverseCtx<'_, ()>) -> bool {
+        match ctx.parent() {
+            Ancestor::CallExpressionArguments(call) => {
+                matches!(call.callee(), Expression::Identifier(id)
+                    if id.name.ends_with(MARKER_SUFFIX) && id.name != SYNC_MARKER)
+            }
+            Ancestor::JSXExpressionContainerExpression(_) => {
+                matches!(ctx.ancestor(1), Ancestor::JSXAttributeValue(attr) if attr.name().is_qrl_prop())
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the function currently being entered is the callback of an array iteration method
+    /// such as `items.map(...)`.
+    fn is_loop_callback(ctx: &TraverseCtx<'_, ()>) -> bool {
+        matches!(ctx.parent(), Ancestor::CallExpressionArguments(call)
+            if call.callee().as_member_expression()
+                .and_then(|member| member.static_property_name())
+                .is_some_and(|name| ITERATION_METHODS.contains(&name)))
+    }
+
+    /// Whether the function currently being entered is the argument of `component$`.
+    fn is_component_closure(ctx: &TraverseCtx<'_, ()>) -> bool {
+        matches!(ctx.parent(), Ancestor::CallExpressionArguments(call)
+            if matches!(call.callee(), Expression::Identifier(id) if id.name == COMPONENT_MARKER))
+    }
+
+    /// Whether the traversal is currently inside a segment rather than the root module.
+    fn in_segment(&self) -> bool {
+        self.import_stack.len() > 1
+    }
+
+    /// Records an exported top-level binding so that segments referencing it import it from the
+    /// root module.
+    fn register_export(&mut self, name: &str, symbol_id: SymbolId, import_id: ImportId) {
+        self.symbol_by_name.insert(name.to_string(), symbol_id);
+        self.import_by_symbol.insert(
+            symbol_id,
+            Import::new(
+                vec![import_id],
+                self.source_info.rel_import_path().to_string_lossy(),
+            ),
+        );
+    }
+
+    /// Exports a non-exported top-level binding referenced from a segment as `_auto_<name>`.
+    fn auto_export(&mut self, name: &str, symbol_id: SymbolId, ctx: &TraverseCtx<'gen, ()>) {
+        let scoping = ctx.scoping();
+        let flags = scoping.symbol_flags(symbol_id);
+        if scoping.symbol_scope_id(symbol_id) != scoping.root_scope_id()
+            || flags.contains(SymbolFlags::Import)
+            || !flags.intersects(SymbolFlags::Variable | SymbolFlags::Function | SymbolFlags::Class)
+        {
+            return;
+        }
+        let import_id = ImportId::NamedWithAlias(auto_export_name(name), name.to_string());
+        self.register_export(name, symbol_id, import_id);
+        self.auto_exports.push(name.to_string());
+    }
+
+    fn import_rest_props(&mut self, uses_rest_props: bool) {
+        if let Some(imports) = self.import_stack.last_mut().filter(|_| uses_rest_props) {
+            imports.insert(Import::new(vec![REST_PROPS_NAME.into()], QWIK_CORE_SOURCE));
+        }
+    }
+
+    /// The bindings captured by a `$` prop function that have to be moved onto its element.
+    fn moved_captures(
+        &self,
+        function: &Expression<'gen>,
+        ctx: &TraverseCtx<'gen, ()>,
+    ) -> Vec<SymbolId> {
+        let handler_scope = match function {
+            Expression::ArrowFunctionExpression(arrow) => arrow.scope_id(),
+            Expression::FunctionExpression(function) => function.scope_id(),
+            _ => return Vec::new(),
+        };
+        let scoping = ctx.scoping();
+        let mut captures = MovedCaptures {
+            scoping,
+            handler_scope,
+            qrl_scope: self
+                .qrl_scope_stack
+                .last()
+                .map_or(scoping.root_scope_id(), |qrl| qrl.scope_id),
+            loop_scopes: &self.loop_scopes,
+            found: Vec::new(),
+        };
+        captures.visit_expression(function);
+        captures.found
+    }
+
+    /// Adds the `q:p` or `q:ps` prop through which the runtime hands the loop bindings of an
+    /// element to its event handlers.
+    fn add_loop_params_prop(&self, jsx: &mut JsxState<'gen>, ctx: &mut TraverseCtx<'gen, ()>) {
+        let mut values: Vec<Expression<'gen>> = jsx
+            .loop_params
+            .iter()
+            .map(|symbol_id| {
+                let name = self.builder.atom(ctx.scoping().symbol_name(*symbol_id));
+                ctx.create_bound_ident_expr(SPAN, name, *symbol_id, ReferenceFlags::Read)
+            })
+            .collect();
+        let (name, value) = if values.len() == 1 {
+            (LOOP_PARAM_PROP, values.remove(0))
+        } else {
+            let elements = self
+                .builder
+                .vec_from_iter(values.into_iter().map(ArrayExpressionElement::from));
+            (
+                LOOP_PARAMS_PROP,
+                self.builder.expression_array(SPAN, elements),
+            )
+        };
+        let key = string_key(&self.builder, SPAN, name);
+        jsx.add_prop(&self.builder, SPAN, key, value, false);
+        jsx.static_subtree = false;
+    }
+
+    /// A JSX element or fragment that may change between renders makes its parent's subtree
+    /// dynamic as well.
+    fn propagate_static_subtree(&mut self, static_subtree: bool, ctx: &TraverseCtx<'gen, ()>) {
+        if !static_subtree && is_jsx_child(ctx) {
+            if let Some(parent) = self.jsx_stack.last_mut() {
+                parent.static_subtree = false;
+            }
+        }
+    }
+
+    /// Extracts the function value of a `$` prop into a segment of its own, returning the QRL
+    /// that replaces it.
+    fn extract_qrl_prop(
+        &mut self,
+        value: Expression<'gen>,
+        stripped: bool,
+        ctx: &mut TraverseCtx<'gen, ()>,
+    ) -> Expression<'gen> {
+        let invalid = self.is_invalid_segment(value.span());
+        let imports: Vec<Import> = self
+            .import_stack
+            .pop()
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        let id = Id::new(
+            self.source_info,
+            &self.segment_stack,
+            &self.options.target,
+            &self.scope,
+        );
+        let (qrl, captures) = if stripped || invalid {
+            self.hoisted.clear();
+            self.noop_segments.push(value.span());
+            let captures = self.noop_captures(&value, invalid, ctx);
+            let qrl = Qrl::new(&id.local_file_name, &id.symbol_name, QrlType::NoopQrl(None));
+            (self.segment_qrl(qrl, &id, value.span()), captures)
+        } else {
+            let comp = QrlComponent::new(
+                &self.options,
+                self.source_info,
+                id,
+                value,
+                std::mem::take(&mut self.hoisted),
+                imports,
+                QrlType::Qrl,
+            );
+            let qrl = self.segment_qrl(comp.qrl.clone(), &comp.id, comp.span);
+            self.components.push(comp);
+            (qrl, Vec::new())
+        };
+        if let Some(imports) = self.import_stack.last_mut() {
+            imports.insert(qrl.import());
+        }
+        let captures = self.captures_array(&captures, ctx);
+        let call = qrl.call_expression_with_captures(
+            ctx,
+            &mut self.symbol_by_name,
+            &mut self.import_by_symbol,
+            captures,
+        );
+        Expression::CallExpression(self.builder.alloc(call))
+    }
+
+    /// The QRL that refers to a segment, which in dev mode also records where the segment was
+    /// declared.
+    fn segment_qrl(&self, qrl: Qrl, id: &Id, span: Span) -> Qrl {
+        if self.options.target == Target::Dev {
+            qrl.with_dev(QrlDev {
+                file: self.dev_file_name(),
+                lo: span.start,
+                hi: span.end,
+                display_name: id.display_name.clone(),
+            })
+        } else {
+            qrl
+        }
+    }
+
+    /// Whether the code of the segment spanning `span` contains an error in dev mode, in which case
+    /// the segment is replaced with a `_noopQrl` so that the rest of the module is still emitted.
+    ///
+    /// An error is attributed to the innermost segment enclosing it, since an enclosing segment
+    /// only refers to a nested one through its QRL.
+    fn is_invalid_segment(&self, span: Span) -> bool {
+        let nested_segments: Vec<Span> = self
+            .components
+            .iter()
+            .map(|comp| comp.span)
+            .chain(self.noop_segments.iter().copied())
+            .filter(|nested| span.contains_inclusive(*nested))
+            .collect();
+        self.options.target == Target::Dev
+            && self
+                .errors
+                .iter()
+                .filter(|error| error.is_error())
+                .map(|error| error.span())
+                .any(|error_span| {
+                    span.contains_inclusive(error_span)
+                        && !nested_segments
+                            .iter()
+                            .any(|nested| nested.contains_inclusive(error_span))
+                })
+    }
+
+    /// Whether the segment of a `$` marker or prop is left out of this build, as configured by
+    /// [TransformOptions::strip_ctx_name] and [TransformOptions::strip_event_handlers].
+    fn is_stripped(&self, ctx_name: &str, is_event_handler: bool) -> bool {
+        (is_event_handler && self.options.strip_event_handlers)
+            || self
+                .options
+                .strip_ctx_name
+                .iter()
+                .any(|prefix| ctx_name.starts_with(prefix.as_str()))
+    }
+
+    /// The captures listed by the `_noopQrl` replacing a segment. A stripped segment keeps its
+    /// captures, while those of an invalid segment are dropped, as they may be the cause of the
+    /// error.
+    fn noop_captures(
+        &self,
+        segment: &Expression<'gen>,
+        invalid: bool,
+        ctx: &TraverseCtx<'gen, ()>,
+    ) -> Vec<SymbolId> {
+        if invalid {
+            Vec::new()
+        } else {
+            self.segment_captures(segment, ctx)
+        }
+    }
+
+    /// The bindings a stripped segment captures, which its `_noopQrl` still lists.
+    fn segment_captures(
+        &self,
+        segment: &Expression<'gen>,
+        ctx: &TraverseCtx<'gen, ()>,
+    ) -> Vec<SymbolId> {
+        let segment_scope = match segment {
+            Expression::ArrowFunctionExpression(arrow) => arrow.scope_id.get(),
+            Expression::FunctionExpression(function) => function.scope_id.get(),
+            _ => None,
+        };
+        let mut captures = SegmentCaptures {
+            scoping: ctx.scoping(),
+            segment_scope,
+            found: Vec::new(),
+        };
+        captures.visit_expression(segment);
+        captures.found
+    }
+
+    /// The array of captured bindings passed to a QRL, if there are any.
+    fn captures_array(
+        &self,
+        captures: &[SymbolId],
+        ctx: &mut TraverseCtx<'gen, ()>,
+    ) -> Option<Expression<'gen>> {
+        if captures.is_empty() {
+            return None;
+        }
+        let elements = self.builder.vec_from_iter(captures.iter().map(|symbol_id| {
+            let name = self.builder.atom(ctx.scoping().symbol_name(*symbol_id));
+            ArrayExpressionElement::from(ctx.create_bound_ident_expr(
+                SPAN,
+                name,
+                *symbol_id,
+                ReferenceFlags::Read,
+            ))
+        }));
+        Some(self.builder.expression_array(SPAN, elements))
+    }
+
+    /// Replaces `sync$(fn)` with `_qrlSync(fn, "<minified fn>")`. The function has to run
+    /// synchronously in the browser, so it is shipped as source instead of being lazy loaded.
+    fn serialize_sync_qrl(&mut self, node: &mut CallExpression<'gen>, ctx: &TraverseCtx<'gen, ()>) {
+        let Some(function) = node.arguments.first().and_then(|arg| arg.as_expression()) else {
+            return;
+        };
+        let function_scope = match function {
+            Expression::ArrowFunctionExpression(arrow) => arrow.scope_id(),
+            Expression::FunctionExpression(function) => function.scope_id(),
+            _ => return,
+        };
+        let mut references = SyncReferences {
+            scoping: ctx.scoping(),
+            function_scope,
+            found: Vec::new(),
+        };
+        references.visit_expression(function);
+        for (name, span) in references.found {
+            self.errors.push(ProcessingFailure::UnsafeCapture(
+                UnsafeCaptureType::SyncReference(name),
+                span,
+            ));
+        }
+        let mut codegen = Codegen::new().with_options(CodegenOptions::minify());
+        codegen.print_expression(function);
+        let source = self.builder.atom(&codegen.into_source_text());
+        node.arguments.truncate(1);
+        node.arguments.push(
+            self.builder
+                .expression_string_literal(SPAN, source, None)
+                .into(),
+        );
+        node.callee = self
+            .builder
+            .expression_identifier(node.callee.span(), QRL_SYNC_NAME);
+        if let Some(imports) = self.import_stack.last_mut() {
+            imports.insert(Import::new(vec![QRL_SYNC_NAME.into()], QWIK_CORE_SOURCE));
+        }
+    }
+
+    /// Resolves an identifier argument of a `$` marker call to the module and export name it is
+    /// imported from, so that the QRL can point straight at it instead of at a new segment.
+    fn imported_qrl_target(
+        &self,
+        arg: &Argument<'_>,
+        ctx: &TraverseCtx<'_, ()>,
+    ) -> Option<(String, String)> {
+        let Argument::Identifier(id) = arg else {
+            return None;
+        };
+        let symbol_id = ctx.scoping().get_reference(id.reference_id()).symbol_id()?;
+        let import = self.import_by_symbol.get(&symbol_id)?;
+        let export_name = match import.names().first()? {
+            ImportId::Named(name) => name.clone(),
+            ImportId::NamedWithAlias(name, _) => name.clone(),
+            ImportId::Default(_) => "default".to_string(),
+            ImportId::Namespace(_) => return None,
+        };
+        Some((import.source().to_string_lossy().to_string(), export_name))
+    }
+
+    /// Detects arguments of a `$` marker call that can not be inlined into a segment of their own.
+    ///
+    /// Inline functions are checked by the capture analysis instead, and imported identifiers are
+    /// expected to have been resolved by [Self::imported_qrl_target] already. Any other argument,
+    /// spreads included, is not a function and is rejected.
+    fn invalid_qrl_argument(
+        &self,
+        marker: &str,
+        arg: &Argument<'_>,
+        ctx: &TraverseCtx<'_, ()>,
+    ) -> Option<QrlArgumentType> {
+        let scoping = ctx.scoping();
+        match arg {
+            Argument::ArrowFunctionExpression(_) | Argument::FunctionExpression(_) => None,
+            Argument::Identifier(id) => {
+                let symbol_id = scoping.get_reference(id.reference_id()).symbol_id()?;
+                let name = id.name.to_string();
+                Some(
+                    if scoping.symbol_scope_id(symbol_id) == scoping.root_scope_id() {
+                        QrlArgumentType::ModuleIdentifier(marker.to_string(), name)
+                    } else {
+                        QrlArgumentType::UnreachableIdentifier(marker.to_string(), name)
+                    },
+                )
+            }
+            _ => Some(QrlArgumentType::NotAFunction(marker.to_string())),
+        }
+    }
+
+    /// Opens the scope of a `$` closure that is being entered.
+    ///
+    /// Must be called while the marker call is the innermost segment, so that the key prefix is
+    /// derived from the same [Id] as the segment the closure is extracted into.
+    fn push_qrl_scope(&mut self, scope_id: ScopeId, captures_this: bool) {
+        let id = Id::new(
+            self.source_info,
+            &self.segment_stack,
+            &self.options.target,
+            &self.scope,
+        );
+        self.qrl_scope_stack.push(QrlScope {
+            scope_id,
+            function_depth: self.function_depth,
+            captures_this,
+            jsx_keys: JsxKeys::new(&id),
+            hoisted: Vec::new(),
+            hoisted_props: HashMap::new(),
+            hoisted_consts: 0,
+            hoisted_fns: 0,
+        });
+    }
+
+    fn pop_qrl_scope(&mut self, scope_id: ScopeId) {
+        if self
+            .qrl_scope_stack
+            .last()
+            .is_some_and(|qrl| qrl.scope_id == scope_id)
+        {
+            self.hoisted = self
+                .qrl_scope_stack
+                .pop()
+                .map(|qrl| qrl.hoisted)
+                .unwrap_or_default();
+        }
+    }
+
+    /// Moves an expression into a module-level constant of the enclosing segment, leaving a
+    /// reference to the constant in its place. Expressions outside of any segment are left as is.
+    fn hoist(
+        &mut self,
+        expr: &mut Expression<'gen>,
+        ctx: &mut TraverseCtx<'gen, ()>,
+    ) -> Option<BoundIdentifier<'gen>> {
+        let qrl = self.qrl_scope_stack.last_mut()?;
+        let name = self
+            .builder
+            .atom(&format!("{HOISTED_PREFIX}{}", qrl.hoisted_consts));
+        qrl.hoisted_consts += 1;
+        let hoisted = qrl.hoist(&self.builder, name, expr, ctx);
+        self.hoisted_symbols.insert(hoisted.symbol_id);
+        Some(hoisted)
+    }
+
+    /// Replaces a JSX element or fragment with the `_jsxSorted` or `_jsxSplit` call that creates
+    /// it, hoisting whatever part of it is static.
+    fn jsx_call(
+        &mut self,
+        jsx: JsxState<'gen>,
+        jsx_type: Expression<'gen>,
+        pure: bool,
+        is_fragment: bool,
+        span: Span,
+        ctx: &mut TraverseCtx<'gen, ()>,
+    ) {
+        let flags = jsx.flags();
+        let type_span = jsx_type.span();
+        let mut args: OxcVec<Argument<'gen>> = OxcVec::from_array_in(
+            [
+                // type
+                jsx_type.into(),
+                // varProps
+                self.jsx_props(span, jsx.var_props, is_fragment).into(),
+                // constProps
+                self.jsx_props(span, jsx.const_props, is_fragment).into(),
+                // children
+                if jsx.is_text_only {
+                    text_only_children(&self.builder, span, jsx.children)
+                } else {
+                    self.builder.expression_array(span, jsx.children)
+                }
+                .into(),
+                // flags
+                self.builder
+                    .expression_numeric_literal(span, flags.into(), None, NumberBase::Decimal)
+                    .into(),
+                // key
+                jsx.key_spreads
+                    .into_iter()
+                    .fold(
+                        jsx.key_prop.unwrap_or_else(|| match jsx.auto_key {
+                            Some(key) => self.builder.expression_string_literal(
+                                Span::default(),
+                                self.builder.atom(&key),
+                                None,
+                            ),
+                            None => self.builder.expression_null_literal(Span::default()),
+                        }),
+                        |key, spread| spread_key(&self.builder, spread, key),
+                    )
+                    .into(),
+            ],
+            self.builder.allocator,
+        );
+        if self.options.target == Target::Dev {
+            args.push(self.jsx_dev_info(span).into());
+        }
+        let callee = if (jsx.should_runtime_sort) {
+            JSX_SPLIT_NAME
+        } else {
+            JSX_SORTED_NAME
+        };
+        let mut call = self.builder.expression_call_with_pure(
+            span,
+            self.builder.expression_identifier(type_span, callee),
+            None::<OxcBox<TSTypeParameterInstantiation<'gen>>>,
+            args,
+            false,
+            pure,
+        );
+        // Const props are hoisted even when the whole element is, so that identical ones are shared.
+        if let Some(const_props) = match &mut call {
+            Expression::CallExpression(jsx_call) => jsx_call.arguments[2].as_expression_mut(),
+            _ => None,
+        } {
+            if matches!(const_props, Expression::ObjectExpression(props) if !props.properties.is_empty())
+                && is_static_expression(const_props, &self.hoisted_symbols, ctx.scoping())
+            {
+                self.hoist_props(const_props, ctx);
+            }
+        }
+        if is_static_expression(&call, &self.hoisted_symbols, ctx.scoping()) {
+            // Static children are left to their parent, so only whole subtrees are hoisted.
+            if !is_jsx_child(ctx) {
+                self.hoist(&mut call, ctx);
+            }
+        } else if let Expression::CallExpression(jsx_call) = &mut call {
+            if let Some(Expression::ArrayExpression(children)) =
+                jsx_call.arguments[3].as_expression_mut()
+            {
+                self.hoist_static_children(&mut children.elements, ctx);
+            }
+        }
+        self.replace_expr = Some(call);
+        if let Some(imports) = self.import_stack.last_mut() {
+            imports.insert(Import::new(vec![callee.into()], QWIK_CORE_SOURCE));
+        }
+        self.propagate_static_subtree(jsx.static_subtree, ctx);
+    }
+
+    /// The 1-based line and column of a byte offset into the source, counting columns in UTF-16
+    /// code units as editors do.
+    fn line_column(&self, offset: u32) -> (usize, usize) {
+        let offset = offset as usize;
+        let line = self.line_starts.partition_point(|start| *start <= offset);
+        let line_start = self.line_starts[line - 1];
+        let column = self.source_text[line_start..offset].encode_utf16().count();
+        (line, column + 1)
+    }
+
+    fn dev_file_name(&self) -> String {
+        self.options
+            .dev_path
+            .clone()
+            .unwrap_or_else(|| self.source_info.rel_path.to_string_lossy().to_string())
+    }
+
+    /// The trailing dev mode argument of a JSX call: `{ fileName, lineNumber, columnNumber }`.
+    fn jsx_dev_info(&self, span: Span) -> Expression<'gen> {
+        let (line, column) = self.line_column(span.start);
+        let file_name = self.builder.atom(&self.dev_file_name());
+        let property = |name: &'static str, value: Expression<'gen>| {
+            self.builder.object_property_kind_object_property(
+                SPAN,
+                PropertyKind::Init,
+                self.builder.property_key_static_identifier(SPAN, name),
+                value,
+                false,
+                false,
+                false,
+            )
+        };
+        let number = |value: usize| {
+            self.builder
+                .expression_numeric_literal(SPAN, value as f64, None, NumberBase::Decimal)
+        };
+        self.builder.expression_object(
+            SPAN,
+            self.builder.vec_from_array([
+                property(
+                    "fileName",
+                    self.builder
+                        .expression_string_literal(SPAN, file_name, None),
+                ),
+                property("lineNumber", number(line)),
+                property("columnNumber", number(column)),
+            ]),
+        )
+    }
+
+    /// The `data-qwik-inspector` value of an element: `file:line:column`.
+    fn inspector_location(&self, span: Span) -> String {
+        let (line, column) = self.line_column(span.start);
+        format!("{}:{line}:{column}", self.dev_file_name())
+    }
+
+    /// Fragments take no props but `key`, so they pass `null` rather than an empty object.
+    fn jsx_props(
+        &self,
+        span: Span,
+        props: OxcVec<'gen, ObjectPropertyKind<'gen>>,
+        is_fragment: bool,
+    ) -> Expression<'gen> {
+        if is_fragment && props.is_empty() {
+            self.builder.expression_null_literal(span)
+        } else {
+            self.builder.expression_object(span, props)
+        }
+    }
+
+    /// Hoists a static const props object, sharing it with any identical one hoisted before.
+    fn hoist_props(&mut self, props: &mut Expression<'gen>, ctx: &mut TraverseCtx<'gen, ()>) {
+        let mut codegen = Codegen::new().with_options(CodegenOptions::minify());
+        codegen.print_expression(props);
+        let source = codegen.into_source_text();
+        let shared = self
+            .qrl_scope_stack
+            .last()
+            .and_then(|qrl| qrl.hoisted_props.get(&source).cloned());
+        match shared {
+            Some(hoisted) => *props = hoisted.create_read_expression(ctx),
+            None => {
+                if let Some(hoisted) = self.hoist(props, ctx) {
+                    if let Some(qrl) = self.qrl_scope_stack.last_mut() {
+                        qrl.hoisted_props.insert(source, hoisted);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Hoists the static elements among the children of an element that is not hoisted itself.
+    fn hoist_static_children(
+        &mut self,
+        children: &mut OxcVec<'gen, ArrayExpressionElement<'gen>>,
+        ctx: &mut TraverseCtx<'gen, ()>,
+    ) {
+        for child in children.iter_mut() {
+            if let Some(child) = child.as_expression_mut() {
+                if matches!(child, Expression::CallExpression(_))
+                    && is_static_expression(child, &self.hoisted_symbols, ctx.scoping())
+                {
+                    self.hoist(child, ctx);
+                }
+            }
+        }
+    }
+
+    /// The key for the JSX element or fragment being entered, if it gets one. Elements inside a
+    /// loop are rendered many times, so a constant key would be shared by all of them.
+    fn auto_key(&mut self, is_fn: bool, ctx: &TraverseCtx<'gen, ()>) -> Option<String> {
+        let scoping = ctx.scoping();
+        let qrl_scope = self.qrl_scope_stack.last().map(|qrl| qrl.scope_id);
+        let segment_scopes = || {
+            scoping
+                .scope_ancestors(ctx.current_scope_id())
+                .take_while(move |scope_id| Some(*scope_id) != qrl_scope)
+        };
+        if segment_scopes().any(|scope_id| self.loop_scopes.contains(&scope_id)) {
+            return None;
+        }
+        // The root is returned by the `$` closure itself rather than by a function nested in it.
+        let function_scope = scoping
+            .scope_ancestors(ctx.current_scope_id())
+            .find(|scope_id| scoping.scope_flags(*scope_id).is_function());
+        let is_root = is_returned_jsx(ctx)
+            && qrl_scope.map_or(function_scope.is_some(), |qrl_scope| {
+                function_scope == Some(qrl_scope)
+            });
+        self.jsx_keys().next_key(is_fn, is_root)
+    }
+
+    fn jsx_keys(&mut self) -> &mut JsxKeys {
+        self.qrl_scope_stack
+            .last_mut()
+            .map_or(&mut self.module_jsx_keys, |qrl| &mut qrl.jsx_keys)
+    }
+
+    /// Whether JSX is rewritten into `_jsxSorted` calls. A check only build still analyzes the JSX,
+    /// but leaves it in place.
+    fn transpiles_jsx(&self) -> bool {
+        self.options.transpile_jsx && !self.options.check_only
+    }
+
+    fn is_capturing_this(&self) -> bool {
+        self.qrl_scope_stack
+            .last()
+            .is_some_and(|qrl| qrl.captures_this && qrl.function_depth == self.function_depth)
+    }
+
+    /// Reports references from within a `$` closure to values that can not be serialized, and
+    /// writes to bindings after they have been captured.
+    fn check_capture(&mut self, id_ref: &IdentifierReference<'_>, ctx: &TraverseCtx<'_, ()>) {
+        let scoping = ctx.scoping();
+        let reference = scoping.get_reference(id_ref.reference_id());
+
+        let Some(symbol_id) = reference.symbol_id() else {
+            if id_ref.name == "arguments" && self.is_capturing_this() {
+                self.errors.push(ProcessingFailure::UnsafeCapture(
+                    UnsafeCaptureType::Arguments,
+                    id_ref.span,
+                ));
+            }
+            return;
+        };
+
+        if let Some(qrl_scope) = self.qrl_scope_stack.last() {
+            let symbol_scope_id = scoping.symbol_scope_id(symbol_id);
+            let is_captured = symbol_scope_id != scoping.root_scope_id()
+                && !scoping
+                    .scope_ancestors(symbol_scope_id)
+                    .any(|scope_id| scope_id == qrl_scope.scope_id);
+
+            if is_captured {
+                if let Some(capture) = self.unsafe_bindings.get(&symbol_id) {
+                    self.errors.push(ProcessingFailure::UnsafeCapture(
+                        capture.clone(),
+                        id_ref.span,
+                    ));
+                }
+                let flags = scoping.symbol_flags(symbol_id);
+                if flags.contains(SymbolFlags::BlockScopedVariable) && !flags.is_const_variable() {
+                    let capturing = self.captured_mutables.entry(symbol_id).or_default();
+                    if !capturing.contains(&qrl_scope.scope_id) {
+                        capturing.push(qrl_scope.scope_id);
+                    }
+                }
+            }
+        }
+
+        let is_written_outside_capture =
+            self.captured_mutables
+                .get(&symbol_id)
+                .is_some_and(|capturing| {
+                    capturing.iter().any(|scope_id| {
+                        !self
+                            .qrl_scope_stack
+                            .iter()
+                            .any(|qrl_scope| qrl_scope.scope_id == *scope_id)
+                    })
+                });
+        if reference.is_write() && is_written_outside_capture {
+            self.errors.push(ProcessingFailure::UnsafeCapture(
+                UnsafeCaptureType::ReassignedLet(id_ref.name.to_string()),
+                id_ref.span,
+            ));
+        }
+    }
 }
 
 fn move_expression<'gen>(
@@ -259,22 +1389,72 @@ fn move_expression<'gen>(
     std::mem::replace(expr, builder.expression_null_literal(span))
 }
 
+/// Byte offsets at which each line of `source` starts.
+fn line_starts(source: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(source.match_indices('\n').map(|(index, _)| index + 1))
+        .collect()
+}
+
+/// The attribute dev tools read the source location of an element from.
+const QWIK_INSPECTOR_ATTRIBUTE: &str = "data-qwik-inspector";
+
+/// Prefix of the module-level constants that static JSX is hoisted into.
+const HOISTED_PREFIX: &str = "_hoisted_";
+const HOISTED_FN_PREFIX: &str = "_hf";
+
+/// The prop whose value is set as raw HTML. The runtime needs its value as a plain string.
+const DANGEROUSLY_SET_INNER_HTML: &str = "dangerouslySetInnerHTML";
+
+/// The prop that sets the CSS classes of an intrinsic element, as a string, array or object.
+const CLASS_PROP: &str = "class";
+
+/// The React spelling of [CLASS_PROP], which is renamed on intrinsic elements.
+const CLASS_NAME_PROP: &str = "className";
+
+/// The prop holding the single loop binding that is passed to the event handlers of an element.
+const LOOP_PARAM_PROP: &str = "q:p";
+
+/// The prop holding the loop bindings when more than one is passed to the event handlers.
+const LOOP_PARAMS_PROP: &str = "q:ps";
+
+/// The prop through which an intrinsic element is handed to a signal or callback once rendered.
+const REF_PROP: &str = "ref";
+
 const DEBUG: bool = true;
 const DUMP_FINAL_AST: bool = false;
 
 impl<'a> Traverse<'a, ()> for TransformGenerator<'a> {
     fn enter_program(&mut self, node: &mut Program<'a>, ctx: &mut TraverseCtx<'a, ()>) {
         println!("ENTERING PROGRAM {}", self.source_info.file_name);
+        let mut generators = GeneratorDeclarations::default();
+        generators.visit_program(node);
+        self.generators = generators.found;
     }
 
     fn exit_program(&mut self, node: &mut Program<'a>, ctx: &mut TraverseCtx<'a, ()>) {
         println!("EXITING PROGRAM {}", self.source_info.file_name);
+        if self.options.check_only {
+            self.app = OptimizedApp {
+                body: String::new(),
+                components: self.components.clone(),
+            };
+            return;
+        }
+
         if let Some(tree) = self.import_stack.pop() {
             tree.iter().for_each(|import| {
                 node.body.insert(0, import.into_in(ctx.ast.allocator));
             });
         }
 
+        for name in self.auto_exports.drain(..) {
+            node.body.push(
+                ctx.ast
+                    .create_local_export_statement(&name, &auto_export_name(&name)),
+            );
+        }
+
         ImportCleanUp::clean_up(node, ctx.ast.allocator);
 
         let codegen_options = CodegenOptions {
@@ -311,11 +1491,25 @@ impl<'a> Traverse<'a, ()> for TransformGenerator<'a> {
         }
 
         let name = node.callee_name().unwrap_or_default().to_string();
-        if (name.ends_with(MARKER_SUFFIX)) {
-            self.import_stack.push(BTreeSet::new());
+        let mut segment: Segment = self.new_segment(&name);
+        if segment.is_qrl() {
+            // A marker call with an argument that can not become a segment is left as it is.
+            let invalid = node
+                .arguments
+                .first()
+                .and_then(|arg0| self.invalid_qrl_argument(&name, arg0, ctx))
+                .filter(|argument| argument.is_error());
+            if let Some(argument) = invalid {
+                self.errors.push(ProcessingFailure::InvalidQrlArgument(
+                    argument,
+                    node.arguments[0].span(),
+                ));
+                segment = Segment::Named(name);
+            } else {
+                self.import_stack.push(BTreeSet::new());
+            }
         }
 
-        let segment: Segment = self.new_segment(name);
         println!("push segment: {segment}");
         self.segment_stack.push(segment);
     }
@@ -325,44 +1519,90 @@ impl<'a> Traverse<'a, ()> for TransformGenerator<'a> {
         node: &mut CallExpression<'a>,
         ctx: &mut TraverseCtx<'a, ()>,
     ) {
+        if node.callee.is_specific_id(SYNC_MARKER) {
+            self.serialize_sync_qrl(node, ctx);
+        }
         let segment = self.segment_stack.last();
 
-        if let Some(segment) = segment {
-            if segment.is_qrl() {
-                let comp = node.arguments.first().map(|arg0| {
-                    let imports = self
-                        .import_stack
-                        .pop()
-                        .unwrap_or_default()
-                        .iter()
-                        .cloned()
-                        .collect();
-
-                    QrlComponent::from_call_expression_argument(
-                        arg0,
+        if let Some(segment) = segment {
+            if segment.is_qrl() {
+                let imports: Vec<Import> = self
+                    .import_stack
+                    .pop()
+                    .unwrap_or_default()
+                    .iter()
+                    .cloned()
+                    .collect();
+
+                let qrl_type = segment.qrl_type();
+                let qrl = node.arguments.first().and_then(|arg0| {
+                    let imported = self.imported_qrl_target(arg0, ctx);
+                    if let (Some((source, export_name)), Some(qrl_type)) =
+                        (imported, qrl_type.clone())
+                    {
+                        return Some((
+                            Qrl::from_import(&source, &export_name, qrl_type),
+                            Vec::new(),
+                        ));
+                    }
+
+                    let marker = node.callee_name().unwrap_or_default();
+                    if let Some(argument) = self.invalid_qrl_argument(marker, arg0, ctx) {
+                        self.errors
+                            .push(ProcessingFailure::InvalidQrlArgument(argument, arg0.span()));
+                    }
+
+                    let invalid = self.is_invalid_segment(arg0.span());
+                    if let Some(qrl_type) =
+                        qrl_type.filter(|_| invalid || self.is_stripped(marker, false))
+                    {
+                        self.hoisted.clear();
+                        let segment = arg0.to_expression();
+                        self.noop_segments.push(segment.span());
+                        let id = Id::new(
+                            self.source_info,
+                            &self.segment_stack,
+                            &self.options.target,
+                            &self.scope,
+                        );
+                        let qrl =
+                            Qrl::new(&id.local_file_name, &id.symbol_name, qrl_type.into_noop());
+                        let qrl = self.segment_qrl(qrl, &id, segment.span());
+                        return Some((qrl, self.noop_captures(segment, invalid, ctx)));
+                    }
+
+                    let comp = QrlComponent::from_expression(
+                        arg0.clone_in(ctx.ast.allocator).into_expression(),
+                        std::mem::take(&mut self.hoisted),
                         imports,
                         &self.segment_stack,
                         &self.scope,
                         &self.options,
                         self.source_info,
-                        ctx.ast.allocator,
-                    )
+                    );
+                    match comp {
+                        Ok(comp) => {
+                            let qrl = self.segment_qrl(comp.qrl.clone(), &comp.id, comp.span);
+                            self.components.push(comp);
+                            Some((qrl, Vec::new()))
+                        }
+                        Err(failure) => {
+                            self.errors.push(failure);
+                            None
+                        }
+                    }
                 });
 
-                if let Some(comp) = &comp {
-                    let qrl = &comp.qrl;
-                    let qrl = qrl.clone();
-                    *node = qrl.into_call_expression(
+                if let Some((qrl, captures)) = qrl {
+                    let captures = self.captures_array(&captures, ctx);
+                    *node = qrl.call_expression_with_captures(
                         ctx,
                         &mut self.symbol_by_name,
                         &mut self.import_by_symbol,
+                        captures,
                     );
-                }
 
-                if let Some(comp) = comp {
-                    let import: Import = comp.qrl.qrl_type.clone().into();
-                    self.qrl_stack.push(comp.qrl.clone());
-                    self.components.push(comp);
+                    let import = qrl.import();
                     let parent_scope = ctx
                         .ancestor_scopes()
                         .last()
@@ -386,6 +1626,28 @@ impl<'a> Traverse<'a, ()> for TransformGenerator<'a> {
     }
 
     fn enter_function(&mut self, node: &mut Function<'a>, ctx: &mut TraverseCtx<'a, ()>) {
+        self.function_depth += 1;
+        if let (Ancestor::ExportNamedDeclarationDeclaration(_), Some(id)) = (ctx.parent(), &node.id)
+        {
+            if let Some(symbol_id) = id.symbol_id.get() {
+                self.register_export(&id.name, symbol_id, ImportId::Named(id.name.to_string()));
+            }
+        }
+        if Self::is_qrl_closure(ctx) {
+            self.push_qrl_scope(node.scope_id(), false);
+        }
+        if Self::is_loop_callback(ctx) {
+            self.loop_scopes.insert(node.scope_id());
+        }
+        if Self::is_component_closure(ctx) {
+            let scope_id = node.scope_id();
+            if let Some(body) = &mut node.body {
+                let uses_rest_props =
+                    destructure_props(&mut node.params, body, &mut false, scope_id, ctx);
+                self.import_rest_props(uses_rest_props);
+            }
+        }
+
         let segment: Segment = node
             .name()
             .map(|n| self.new_segment(n))
@@ -397,22 +1659,77 @@ impl<'a> Traverse<'a, ()> for TransformGenerator<'a> {
     fn exit_function(&mut self, node: &mut Function<'a>, ctx: &mut TraverseCtx<'a, ()>) {
         let popped = self.segment_stack.pop();
         println!("pop segment: {popped:?}");
+
+        self.pop_qrl_scope(node.scope_id());
+        self.function_depth -= 1;
     }
 
-    fn exit_argument(&mut self, node: &mut Argument<'a>, ctx: &mut TraverseCtx<'a, ()>) {
-        if let Argument::CallExpression(call_expr) = node {
-            let qrl = self.qrl_stack.pop();
+    fn enter_arrow_function_expression(
+        &mut self,
+        node: &mut ArrowFunctionExpression<'a>,
+        ctx: &mut TraverseCtx<'a, ()>,
+    ) {
+        if Self::is_qrl_closure(ctx) {
+            self.push_qrl_scope(node.scope_id(), true);
+        }
+        if Self::is_loop_callback(ctx) {
+            self.loop_scopes.insert(node.scope_id());
+        }
+        if Self::is_component_closure(ctx) {
+            let scope_id = node.scope_id();
+            let uses_rest_props = destructure_props(
+                &mut node.params,
+                &mut node.body,
+                &mut node.expression,
+                scope_id,
+                ctx,
+            );
+            self.import_rest_props(uses_rest_props);
+        }
+    }
 
-            if let Some(qrl) = qrl {
-                let idr = qrl.into_identifier_reference(
-                    ctx,
-                    &mut self.symbol_by_name,
-                    &mut self.import_by_symbol,
-                );
-                let args: OxcVec<'a, Argument<'a>> = qrl.into_in(ctx.ast.allocator);
+    fn exit_arrow_function_expression(
+        &mut self,
+        node: &mut ArrowFunctionExpression<'a>,
+        ctx: &mut TraverseCtx<'a, ()>,
+    ) {
+        self.pop_qrl_scope(node.scope_id());
+    }
+
+    fn enter_for_statement(&mut self, node: &mut ForStatement<'a>, ctx: &mut TraverseCtx<'a, ()>) {
+        self.loop_scopes.insert(node.scope_id());
+    }
+
+    fn enter_for_in_statement(
+        &mut self,
+        node: &mut ForInStatement<'a>,
+        ctx: &mut TraverseCtx<'a, ()>,
+    ) {
+        self.loop_scopes.insert(node.scope_id());
+    }
+
+    fn enter_for_of_statement(
+        &mut self,
+        node: &mut ForOfStatement<'a>,
+        ctx: &mut TraverseCtx<'a, ()>,
+    ) {
+        self.loop_scopes.insert(node.scope_id());
+    }
+
+    fn enter_this_expression(&mut self, node: &mut ThisExpression, ctx: &mut TraverseCtx<'a, ()>) {
+        if self.is_capturing_this() {
+            self.errors.push(ProcessingFailure::UnsafeCapture(
+                UnsafeCaptureType::This,
+                node.span,
+            ));
+        }
+    }
 
-                call_expr.callee = Expression::Identifier(OxcBox::new_in(idr, ctx.ast.allocator));
-                call_expr.arguments = args
+    fn enter_class(&mut self, node: &mut Class<'a>, ctx: &mut TraverseCtx<'a, ()>) {
+        if let (Ancestor::ExportNamedDeclarationDeclaration(_), Some(id)) = (ctx.parent(), &node.id)
+        {
+            if let Some(symbol_id) = id.symbol_id.get() {
+                self.register_export(&id.name, symbol_id, ImportId::Named(id.name.to_string()));
             }
         }
     }
@@ -440,6 +1757,28 @@ impl<'a> Traverse<'a, ()> for TransformGenerator<'a> {
             });
         }
 
+        if let (Some(name), Some(init)) = (id.get_identifier_name(), &node.init) {
+            let symbol_id = id.get_binding_identifier().and_then(|b| b.symbol_id.get());
+            if let Some(symbol_id) = symbol_id {
+                if matches!(init.without_parentheses(), Expression::FunctionExpression(f) if f.generator)
+                {
+                    self.generators.insert(symbol_id);
+                }
+                let capture = init.unsafe_capture(&name, |callee| {
+                    let Expression::Identifier(callee) = callee else {
+                        return false;
+                    };
+                    ctx.scoping()
+                        .get_reference(callee.reference_id())
+                        .symbol_id()
+                        .is_some_and(|symbol_id| self.generators.contains(&symbol_id))
+                });
+                if let Some(capture) = capture {
+                    self.unsafe_bindings.insert(symbol_id, capture);
+                }
+            }
+        }
+
         if let Some(name) = id.get_identifier_name() {
             /// Adds symbol and import information in the case this declaration ends up being referenced in
             /// an exported component.
@@ -447,15 +1786,7 @@ impl<'a> Traverse<'a, ()> for TransformGenerator<'a> {
             if let Ancestor::ExportNamedDeclarationDeclaration(export) = grandparent {
                 let symbol_id = id.get_binding_identifier().and_then(|b| b.symbol_id.get());
                 if let Some(symbol_id) = symbol_id {
-                    self.symbol_by_name.insert(name.to_string(), symbol_id);
-                    let import_id = ImportId::Named(name.to_string());
-                    self.import_by_symbol.insert(
-                        symbol_id,
-                        Import::new(
-                            vec![import_id],
-                            self.source_info.rel_import_path().to_string_lossy(),
-                        ),
-                    );
+                    self.register_export(&name, symbol_id, ImportId::Named(name.to_string()));
                 }
             }
         }
@@ -466,17 +1797,6 @@ impl<'a> Traverse<'a, ()> for TransformGenerator<'a> {
         node: &mut VariableDeclarator<'a>,
         ctx: &mut TraverseCtx<'a, ()>,
     ) {
-        if let Some(init) = &mut node.init {
-            let qrl = self.qrl_stack.pop();
-            if let Some(qrl) = qrl {
-                node.init = Some(qrl.into_expression(
-                    ctx,
-                    &mut self.symbol_by_name,
-                    &mut self.import_by_symbol,
-                ));
-            }
-        }
-
         // If this definition is constant, mark it as constant within the current scope
         if self.options.transpile_jsx && self.expr_is_const_stack.pop().unwrap_or_default() {
             if let Some(consts) = self.const_stack.last_mut() {
@@ -549,21 +1869,19 @@ impl<'a> Traverse<'a, ()> for TransformGenerator<'a> {
                     false,
                     is_text_only(name.into()),
                 )
+            } else if let JSXElementName::NamespacedName(name) = &node.opening_element.name {
+                (Some(self.new_segment(name.to_string())), false, false)
             } else {
                 (None, true, false)
             };
-        self.jsx_stack.push(JsxState {
+        let auto_key = self.auto_key(is_fn, ctx);
+        self.jsx_stack.push(JsxState::new(
             is_fn,
             is_text_only,
-            is_segment: segment.is_some(),
-            should_runtime_sort: false,
-            static_listeners: true,
-            static_subtree: true,
-            key_prop: None,
-            var_props: OxcVec::new_in(self.builder.allocator),
-            const_props: OxcVec::new_in(self.builder.allocator),
-            children: OxcVec::new_in(self.builder.allocator),
-        });
+            segment.is_some(),
+            auto_key,
+            self.builder.allocator,
+        ));
         if let Some(segment) = segment {
             self.debug(format!("ENTER: JSXElementName {segment}"), ctx);
             println!("push segment: {segment}");
@@ -573,7 +1891,11 @@ impl<'a> Traverse<'a, ()> for TransformGenerator<'a> {
 
     fn exit_jsx_element(&mut self, node: &mut JSXElement<'a>, ctx: &mut TraverseCtx<'a, ()>) {
         if let Some(mut jsx) = self.jsx_stack.pop() {
-            if (self.options.transpile_jsx) {
+            let is_segment = jsx.is_segment;
+            if self.transpiles_jsx() {
+                if !jsx.loop_params.is_empty() {
+                    self.add_loop_params_prop(&mut jsx, ctx);
+                }
                 if (!jsx.should_runtime_sort) {
                     jsx.var_props.sort_by_key(|prop| match prop {
                         ObjectPropertyKind::ObjectProperty(b) => match &(*b).key {
@@ -598,7 +1920,12 @@ impl<'a> Traverse<'a, ()> for TransformGenerator<'a> {
                         false,
                     ),
                     JSXElementName::NamespacedName(b) => {
-                        panic!("namespaced names in JSX not implemented")
+                        let name = self.builder.atom(&b.to_string());
+                        (
+                            self.builder
+                                .expression_string_literal(b.span, name, Some(name)),
+                            true,
+                        )
                     }
                     JSXElementName::MemberExpression(b) => {
                         fn process_member_expr<'b>(
@@ -642,77 +1969,27 @@ impl<'a> Traverse<'a, ()> for TransformGenerator<'a> {
                         (self.builder.expression_this((*b).span), false)
                     }
                 };
-                let args: OxcVec<Argument<'a>> = OxcVec::from_array_in(
-                    [
-                        // type
-                        jsx_type.into(),
-                        // varProps
-                        self.builder
-                            .expression_object(node.span(), jsx.var_props)
-                            .into(),
-                        // constProps
-                        self.builder
-                            .expression_object(node.span(), jsx.const_props)
-                            .into(),
-                        // children
-                        self.builder
-                            .expression_array(node.span(), jsx.children)
-                            .into(),
-                        // flags
-                        self.builder
-                            .expression_numeric_literal(
-                                node.span(),
-                                ((if jsx.static_subtree { 0b1 } else { 0 })
-                                    | (if jsx.static_listeners { 0b01 } else { 0 }))
-                                .into(),
-                                None,
-                                NumberBase::Binary,
-                            )
-                            .into(),
-                        // key
-                        jsx.key_prop
-                            .unwrap_or_else(|| -> Expression<'a> {
-                                // TODO: Figure out how to replicate root_jsx_mode from old optimizer
-                                // (this conditional should be is_fn || root_jsx_mode)
-                                if jsx.is_fn {
-                                    if let Some(cmp) = self.component_stack.last() {
-                                        let new_key = format!(
-                                            "{}_{}",
-                                            cmp.id.hash.chars().take(2).collect::<String>(),
-                                            self.jsx_key_counter
-                                        );
-                                        self.jsx_key_counter += 1;
-                                        return self.builder.expression_string_literal(
-                                            Span::default(),
-                                            self.builder.atom(&new_key),
-                                            None,
-                                        );
-                                    }
-                                }
-                                self.builder.expression_null_literal(Span::default())
-                            })
-                            .into(),
-                    ],
-                    self.builder.allocator,
-                );
-                let callee = if (jsx.should_runtime_sort) {
-                    JSX_SPLIT_NAME
-                } else {
-                    JSX_SORTED_NAME
-                };
-                self.replace_expr = Some(self.builder.expression_call_with_pure(
-                    node.span,
-                    self.builder.expression_identifier(name.span(), callee),
-                    None::<OxcBox<TSTypeParameterInstantiation<'a>>>,
-                    args,
-                    false,
-                    pure,
-                ));
-                if let Some(imports) = self.import_stack.last_mut() {
-                    imports.insert(Import::new(vec![callee.into()], QWIK_CORE_SOURCE));
+                let is_fragment = matches!(name, JSXElementName::IdentifierReference(id) if id.name == FRAGMENT_NAME);
+                if self.options.jsx_inspector && self.options.target == Target::Dev && !jsx.is_fn {
+                    let location = self.inspector_location(node.span);
+                    let location = self.builder.expression_string_literal(
+                        SPAN,
+                        self.builder.atom(&location),
+                        None,
+                    );
+                    let key = string_key(&self.builder, SPAN, QWIK_INSPECTOR_ATTRIBUTE);
+                    jsx.add_prop(&self.builder, SPAN, key, location, true);
                 }
+                self.jsx_call(
+                    jsx,
+                    jsx_type,
+                    pure || is_fragment,
+                    is_fragment,
+                    node.span,
+                    ctx,
+                );
             }
-            if jsx.is_segment {
+            if is_segment {
                 let popped = self.segment_stack.pop();
             }
         }
@@ -721,25 +1998,25 @@ impl<'a> Traverse<'a, ()> for TransformGenerator<'a> {
     }
 
     fn enter_jsx_fragment(&mut self, node: &mut JSXFragment<'a>, ctx: &mut TraverseCtx<'a, ()>) {
-        self.jsx_stack.push(JsxState {
-            is_fn: false,
-            is_text_only: false,
-            is_segment: false,
-            should_runtime_sort: false,
-            static_listeners: true,
-            static_subtree: true,
-            key_prop: None,
-            var_props: OxcVec::new_in(self.builder.allocator),
-            const_props: OxcVec::new_in(self.builder.allocator),
-            children: OxcVec::new_in(self.builder.allocator),
-        });
+        let auto_key = self.auto_key(true, ctx);
+        self.jsx_stack.push(JsxState::new(
+            false,
+            false,
+            false,
+            auto_key,
+            self.builder.allocator,
+        ));
         self.debug("ENTER: JSXFragment", ctx);
     }
 
     fn exit_jsx_fragment(&mut self, node: &mut JSXFragment<'a>, ctx: &mut TraverseCtx<'a, ()>) {
-        if let Some(mut jsx) = self.jsx_stack.pop() {
-            if (self.options.transpile_jsx) {
-                self.replace_expr = Some(self.builder.expression_array(node.span(), jsx.children));
+        if let Some(jsx) = self.jsx_stack.pop() {
+            if self.transpiles_jsx() {
+                let fragment = self.builder.expression_identifier(node.span, FRAGMENT_NAME);
+                if let Some(imports) = self.import_stack.last_mut() {
+                    imports.insert(Import::new(vec![FRAGMENT_NAME.into()], QWIK_CORE_SOURCE));
+                }
+                self.jsx_call(jsx, fragment, true, true, node.span, ctx);
             }
         }
         self.debug("EXIT: JSXFragment", ctx);
@@ -750,11 +2027,13 @@ impl<'a> Traverse<'a, ()> for TransformGenerator<'a> {
         node: &mut JSXSpreadAttribute<'a>,
         ctx: &mut TraverseCtx<'a, ()>,
     ) {
-        if (!self.options.transpile_jsx) {
+        if !self.transpiles_jsx() {
             return;
         }
         // Reference: qwik build/v2 internal_handle_jsx_props_obj
-        // If we have spread props, all props that come before it are variable even if they're static
+        // The spread may override any prop that comes before it, so those props are all variable and
+        // keep their order. Const props that come after the last spread still override it from
+        // `constProps`.
         if let Some(jsx) = self.jsx_stack.last_mut() {
             let range = 0..jsx.const_props.len();
             jsx.const_props
@@ -763,6 +2042,13 @@ impl<'a> Traverse<'a, ()> for TransformGenerator<'a> {
             jsx.should_runtime_sort = true;
             jsx.static_subtree = false;
             jsx.static_listeners = false;
+            // Reading `key` from anything but a plain reference would evaluate the spread twice.
+            if is_reference(&node.argument) {
+                jsx.key_spreads.push(
+                    node.argument
+                        .clone_in_with_semantic_ids(self.builder.allocator),
+                );
+            }
             jsx.var_props
                 .push(self.builder.object_property_kind_spread_property(
                     node.span(),
@@ -772,34 +2058,91 @@ impl<'a> Traverse<'a, ()> for TransformGenerator<'a> {
     }
 
     fn enter_jsx_attribute(&mut self, node: &mut JSXAttribute<'a>, ctx: &mut TraverseCtx<'a, ()>) {
-        if (self.options.transpile_jsx) {
-            self.expr_is_const_stack.push(
-                self.jsx_stack
-                    .last()
-                    .map_or(false, |jsx| !jsx.should_runtime_sort),
-            );
+        if self.transpiles_jsx() {
+            self.expr_is_const_stack.push(!self.jsx_stack.is_empty());
         }
         self.ascend();
         self.debug("ENTER: JSXAttribute", ctx);
         // JSX Attributes should be treated as part of the segment scope.
-        let segment: Segment = self.new_segment(node.name.get_identifier().name);
+        let segment: Segment = self.new_segment(node.name.full_name());
         self.segment_stack.push(segment);
+        if has_qrl_prop_value(node) {
+            self.import_stack.push(BTreeSet::new());
+        }
     }
 
     fn exit_jsx_attribute(&mut self, node: &mut JSXAttribute<'a>, ctx: &mut TraverseCtx<'a, ()>) {
-        if (self.options.transpile_jsx) {
+        let is_qrl = has_qrl_prop_value(node);
+        if is_qrl {
+            if let Some(JSXAttributeValue::ExpressionContainer(container)) = &mut node.value {
+                let value = container.expression.to_expression_mut();
+                let moved_captures = self.moved_captures(value, ctx);
+                if let Some(jsx) = self.jsx_stack.last_mut().filter(|jsx| !jsx.is_fn) {
+                    // Rather than capturing the bindings of a loop iteration, an event handler is
+                    // handed them by the runtime from the `q:p` prop of its element.
+                    let moves_to_params =
+                        node.name.event_attribute_name().is_some() && accepts_loop_params(value);
+                    if !moves_to_params {
+                        jsx.moved_captures |= !moved_captures.is_empty();
+                    } else if !moved_captures.is_empty() {
+                        for symbol_id in moved_captures {
+                            if !jsx.loop_params.contains(&symbol_id) {
+                                jsx.loop_params.push(symbol_id);
+                            }
+                        }
+                        add_loop_params(&self.builder, value, &jsx.loop_params, ctx.scoping());
+                    }
+                }
+                let stripped = self.is_stripped(
+                    &node.name.full_name(),
+                    node.name.event_attribute_name().is_some(),
+                );
+                let function = move_expression(&self.builder, value);
+                *value = self.extract_qrl_prop(function, stripped, ctx);
+            }
+        }
+        // A ref is assigned by the runtime once the element exists, so it is neither const nor
+        // tracked like a reactive value.
+        let is_ref = self.jsx_stack.last().is_some_and(|jsx| !jsx.is_fn)
+            && node.name.full_name() == REF_PROP;
+        let is_class = self.jsx_stack.last().is_some_and(|jsx| !jsx.is_fn)
+            && matches!(node.name.full_name().as_str(), CLASS_PROP | CLASS_NAME_PROP);
+        let mut duplicate_class = false;
+        if let Some(jsx) = self.jsx_stack.last_mut().filter(|_| is_class) {
+            duplicate_class = jsx.has_class;
+            jsx.has_class = true;
+        }
+        let binding_dev = node
+            .name
+            .binding()
+            .filter(|_| self.options.target == Target::Dev)
+            .map(|_| QrlDev {
+                file: self.dev_file_name(),
+                lo: node.span.start,
+                hi: node.span.end,
+                display_name: Id::new(
+                    self.source_info,
+                    &self.segment_stack,
+                    &self.options.target,
+                    &self.scope,
+                )
+                .display_name,
+            });
+        if self.transpiles_jsx() {
             if let Some(jsx) = self.jsx_stack.last_mut() {
-                let expr: Expression<'a> = {
+                let mut expr: Expression<'a> = {
                     let v = &mut node.value;
                     match v {
                         None => self.builder.expression_boolean_literal(node.span, true),
-                        Some(JSXAttributeValue::Element(_)) => {
+                        Some(JSXAttributeValue::Element(_) | JSXAttributeValue::Fragment(_)) => {
                             println!("Replacing JSX attribute element on exit");
-                            self.replace_expr.take().unwrap()
-                        }
-                        Some(JSXAttributeValue::Fragment(_)) => {
-                            println!("Replacing JSX attribute fragment on exit");
-                            self.replace_expr.take().unwrap()
+                            self.replace_expr.take().unwrap_or_else(|| {
+                                self.errors.push(ProcessingFailure::Internal(
+                                    "JSX attribute value was not transformed".to_string(),
+                                    node.span,
+                                ));
+                                self.builder.expression_null_literal(node.span)
+                            })
                         }
                         Some(JSXAttributeValue::StringLiteral(b)) => self
                             .builder
@@ -809,83 +2152,180 @@ impl<'a> Traverse<'a, ()> for TransformGenerator<'a> {
                         }
                     }
                 };
-                let is_const = self.expr_is_const_stack.pop().unwrap_or_default();
+                let mut is_const = self.expr_is_const_stack.pop().unwrap_or_default();
+                if is_qrl || is_sync_qrl(&expr) {
+                    // Whatever the closure references, the extracted QRL itself never changes, and
+                    // a `sync$` function may only use globals, as reported by serialize_sync_qrl.
+                    is_const = true;
+                }
+                if is_class {
+                    if duplicate_class {
+                        // The last of the two wins, as it would in a plain object literal.
+                        let is_class_prop = |prop: &ObjectPropertyKind<'a>| {
+                            matches!(prop, ObjectPropertyKind::ObjectProperty(prop)
+                                if prop.key.is_specific_static_name(CLASS_PROP))
+                        };
+                        jsx.const_props.retain(|prop| !is_class_prop(prop));
+                        jsx.var_props.retain(|prop| !is_class_prop(prop));
+                    }
+                    if matches!(
+                        expr,
+                        Expression::ArrayExpression(_) | Expression::ObjectExpression(_)
+                    ) && is_static_expression(&expr, &self.hoisted_symbols, ctx.scoping())
+                    {
+                        is_const = true;
+                    }
+                }
+                // Like their children, the inner HTML of text-only elements is set as a plain string.
+                let is_text_only_content =
+                    jsx.is_text_only && node.name.full_name() == DANGEROUSLY_SET_INNER_HTML;
+                if !node.is_key()
+                    && !is_ref
+                    && !is_text_only_content
+                    && matches!(node.value, Some(JSXAttributeValue::ExpressionContainer(_)))
+                {
+                    if let Some(helper) =
+                        convert_reactive_expression(&self.builder, ctx.scoping(), &mut expr)
+                    {
+                        is_const = true;
+                        if let Some(imports) = self.import_stack.last_mut() {
+                            imports.insert(Import::new(vec![helper.into()], QWIK_CORE_SOURCE));
+                        }
+                        if let Some(qrl) = self
+                            .qrl_scope_stack
+                            .last_mut()
+                            .filter(|_| helper == FN_SIGNAL_NAME)
+                        {
+                            qrl.hoist_fn_signal(&self.builder, &mut expr, ctx);
+                        }
+                    }
+                }
+                let event = node.name.event_attribute_name().filter(|_| !jsx.is_fn);
+                let binding = node.name.binding().filter(|_| !jsx.is_fn);
                 if node.is_key() {
                     jsx.key_prop = Some(expr);
-                } else {
-                    let props = if is_const {
-                        &mut jsx.const_props
+                    jsx.key_spreads.clear();
+                } else if let Some((prop, handler)) = binding {
+                    // `bind:value={sig}` sets `value` and writes back to `sig` on input.
+                    let callee = if binding_dev.is_some() {
+                        INLINED_QRL_DEV
                     } else {
-                        &mut jsx.var_props
+                        INLINED_QRL
                     };
-                    props.push(self.builder.object_property_kind_object_property(
-                        node.span,
-                        PropertyKind::Init,
+                    if let Some(imports) = self.import_stack.last_mut() {
+                        imports.insert(Import::new(
+                            vec![handler.into(), callee.into()],
+                            QWIK_CORE_SOURCE,
+                        ));
+                    }
+                    let handler =
+                        inlined_handler(&self.builder, handler, &expr, binding_dev.as_ref());
+                    let is_const = true;
+                    let key = self
+                        .builder
+                        .property_key_static_identifier(node.name.span(), prop);
+                    jsx.add_prop(&self.builder, node.span, key, expr, is_const);
+                    jsx.add_event_handler(&self.builder, node.span, "q-e:input", handler, is_const);
+                } else if let Some(event) = event {
+                    jsx.add_event_handler(&self.builder, node.span, &event, expr, is_const);
+                } else if is_ref {
+                    let key = self
+                        .builder
+                        .property_key_static_identifier(node.name.span(), REF_PROP);
+                    if !is_const {
+                        jsx.static_subtree = false;
+                    }
+                    jsx.add_prop(&self.builder, node.span, key, expr, false);
+                } else if is_class {
+                    let key = self
+                        .builder
+                        .property_key_static_identifier(node.name.span(), CLASS_PROP);
+                    if !is_const {
+                        jsx.static_subtree = false;
+                    }
+                    jsx.add_prop(&self.builder, node.span, key, expr, is_const);
+                } else {
+                    let key = if node.name.is_identifier_key() {
                         self.builder.property_key_static_identifier(
                             node.name.span(),
                             node.name.get_identifier().name,
-                        ),
-                        expr,
-                        false,
-                        false,
-                        false,
-                    ));
+                        )
+                    } else {
+                        string_key(&self.builder, node.name.span(), &node.name.full_name())
+                    };
+                    if !is_const {
+                        jsx.static_subtree = false;
+                    }
+                    jsx.add_prop(&self.builder, node.span, key, expr, is_const);
                 }
             }
         }
+        if duplicate_class {
+            self.errors
+                .push(ProcessingFailure::DuplicateClass(node.span));
+        }
         let popped = self.segment_stack.pop();
         self.debug("EXIT: JSXAttribute", ctx);
         self.descend();
     }
 
-    fn exit_jsx_attribute_value(
-        &mut self,
-        node: &mut JSXAttributeValue<'a>,
-        ctx: &mut TraverseCtx<'a, ()>,
-    ) {
-        if let JSXAttributeValue::ExpressionContainer(container) = node {
-            let qrl = self.qrl_stack.pop();
-
-            if let Some(qrl) = qrl {
-                container.expression = qrl.into_jsx_expression(
-                    ctx,
-                    &mut self.symbol_by_name,
-                    &mut self.import_by_symbol,
-                )
-            }
+    fn enter_jsx_child(&mut self, node: &mut JSXChild<'a>, ctx: &mut TraverseCtx<'a, ()>) {
+        if self.transpiles_jsx() && matches!(node, JSXChild::ExpressionContainer(_)) {
+            self.expr_is_const_stack.push(true);
         }
     }
 
     fn exit_jsx_child(&mut self, node: &mut JSXChild<'a>, ctx: &mut TraverseCtx<'a, ()>) {
-        if (!self.options.transpile_jsx) {
+        if !self.transpiles_jsx() {
             return;
         }
         self.debug("EXIT: JSX child", ctx);
         if let Some(jsx) = self.jsx_stack.last_mut() {
             let maybe_child = match node {
-                JSXChild::Text(b) => {
-                    let text: &'a str = self.builder.allocator.alloc_str(b.value.trim());
-                    if (text.is_empty()) {
-                        None
-                    } else {
-                        Some(
-                            self.builder
-                                .expression_string_literal((*b).span, text, Some(text.into()))
-                                .into(),
-                        )
-                    }
-                }
-                JSXChild::Element(_) => {
+                JSXChild::Text(b) => b.rendered_text().map(|text| {
+                    let text = self.builder.atom(&text);
+                    self.builder
+                        .expression_string_literal(b.span, text, None)
+                        .into()
+                }),
+                JSXChild::Element(_) | JSXChild::Fragment(_) => {
                     println!("Replacing JSX child element on exit");
-                    Some(self.replace_expr.take().unwrap().into())
-                }
-                JSXChild::Fragment(_) => {
-                    println!("Replacing JSX child fragment on exit");
-                    Some(self.replace_expr.take().unwrap().into())
+                    let replaced = self.replace_expr.take();
+                    if replaced.is_none() {
+                        self.errors.push(ProcessingFailure::Internal(
+                            "JSX child was not transformed".to_string(),
+                            node.span(),
+                        ));
+                    }
+                    replaced.map(ArrayExpressionElement::from)
                 }
                 JSXChild::ExpressionContainer(b) => {
-                    jsx.static_subtree = false;
-                    Some(move_expression(&self.builder, (*b).expression.to_expression_mut()).into())
+                    let mut is_const = self.expr_is_const_stack.pop().unwrap_or_default();
+                    let mut expr =
+                        move_expression(&self.builder, (*b).expression.to_expression_mut());
+                    // The content of text-only elements is rendered as a plain string.
+                    let helper = (!jsx.is_text_only)
+                        .then(|| {
+                            convert_reactive_expression(&self.builder, ctx.scoping(), &mut expr)
+                        })
+                        .flatten();
+                    if let Some(helper) = helper {
+                        is_const = true;
+                        if let Some(imports) = self.import_stack.last_mut() {
+                            imports.insert(Import::new(vec![helper.into()], QWIK_CORE_SOURCE));
+                        }
+                        if let Some(qrl) = self
+                            .qrl_scope_stack
+                            .last_mut()
+                            .filter(|_| helper == FN_SIGNAL_NAME)
+                        {
+                            qrl.hoist_fn_signal(&self.builder, &mut expr, ctx);
+                        }
+                    }
+                    if !is_const {
+                        jsx.static_subtree = false;
+                    }
+                    Some(expr.into())
                 }
                 JSXChild::Spread(b) => {
                     jsx.static_subtree = false;
@@ -902,26 +2342,6 @@ impl<'a> Traverse<'a, ()> for TransformGenerator<'a> {
         }
     }
 
-    fn exit_return_statement(
-        &mut self,
-        node: &mut ReturnStatement<'a>,
-        ctx: &mut TraverseCtx<'a, ()>,
-    ) {
-        if let Some(expr) = &node.argument {
-            if expr.is_qrl_replaceable() {
-                let qrl = self.qrl_stack.pop();
-                if let Some(qrl) = qrl {
-                    let expression = qrl.into_expression(
-                        ctx,
-                        &mut self.symbol_by_name,
-                        &mut self.import_by_symbol,
-                    );
-                    node.argument = Some(expression);
-                }
-            }
-        }
-    }
-
     fn enter_statements(
         &mut self,
         node: &mut OxcVec<'a, Statement<'a>>,
@@ -1072,6 +2492,21 @@ impl<'a> Traverse<'a, ()> for TransformGenerator<'a> {
         id_ref: &mut IdentifierReference<'a>,
         ctx: &mut TraverseCtx<'a, ()>,
     ) {
+        let symbol_id = id_ref
+            .reference_id
+            .get()
+            .and_then(|ref_id| ctx.scoping().get_reference(ref_id).symbol_id());
+        if let Some(symbol_id) = symbol_id {
+            let is_const = ctx.scoping().symbol_scope_id(symbol_id)
+                == ctx.scoping().root_scope_id()
+                || self
+                    .const_stack
+                    .iter()
+                    .any(|consts| consts.contains(&symbol_id));
+            if let Some(is_const_expr) = self.expr_is_const_stack.last_mut().filter(|_| !is_const) {
+                *is_const_expr = false;
+            }
+        }
         if let Some(illegal_code_type) = id_ref
             .reference_id
             .get()
@@ -1080,17 +2515,44 @@ impl<'a> Traverse<'a, ()> for TransformGenerator<'a> {
             .and_then(|refr| refr.symbol_id())
             .and_then(|symbol_id| self.removed.get(&symbol_id))
         {
-            self.errors.push(illegal_code_type.into());
+            self.errors.push(ProcessingFailure::IllegalCode(
+                illegal_code_type.clone(),
+                id_ref.span,
+            ));
         }
 
+        self.check_capture(id_ref, ctx);
+
         // Whilst visiting each identifier reference, we check if that references refers to an import.
         // If so, we store on the current import stack so that it can be used later in the `exit_expression`
         // logic that ends up creating a new module/component.
         let ref_id = id_ref.reference_id();
         if let Some(symbol_id) = ctx.scoping.scoping().get_reference(ref_id).symbol_id() {
+            if !self.import_by_symbol.contains_key(&symbol_id)
+                && self.in_segment()
+                && !id_ref.name.ends_with(MARKER_SUFFIX)
+            {
+                self.auto_export(&id_ref.name, symbol_id, ctx);
+            }
+            // Marker calls rejected in `enter_call_expression` are kept as they are, so they import
+            // the marker itself rather than its QRL equivalent.
+            let is_kept_marker = matches!(ctx.parent(), Ancestor::CallExpressionCallee(_))
+                && self
+                    .segment_stack
+                    .last()
+                    .is_some_and(|segment| !segment.is_qrl());
             if let Some(import) = self.import_by_symbol.get(&symbol_id) {
                 let import = import.clone();
-                if !id_ref.name.ends_with(MARKER_SUFFIX) {
+                let is_local = import.source() == &self.source_info.rel_import_path();
+                if id_ref.name.ends_with(MARKER_SUFFIX) && is_kept_marker {
+                    let marker = Import::new(
+                        vec![id_ref.name.as_str().into()],
+                        import.source().to_string_lossy(),
+                    );
+                    self.import_stack.last_mut().unwrap().insert(marker);
+                }
+                // The root module already declares its own exports.
+                if !id_ref.name.ends_with(MARKER_SUFFIX) && (self.in_segment() || !is_local) {
                     self.import_stack.last_mut().unwrap().insert(import);
                 }
             }
@@ -1098,6 +2560,293 @@ impl<'a> Traverse<'a, ()> for TransformGenerator<'a> {
     }
 }
 
+fn auto_export_name(name: &str) -> String {
+    format!("{AUTO_EXPORT_PREFIX}{name}")
+}
+
+/// Whether the JSX element being exited is a direct child of another element or fragment.
+fn is_jsx_child(ctx: &TraverseCtx<'_, ()>) -> bool {
+    matches!(
+        ctx.parent(),
+        Ancestor::JSXElementChildren(_) | Ancestor::JSXFragmentChildren(_)
+    )
+}
+
+/// Whether the JSX element or fragment being entered is the value returned by the function it is
+/// in, either from a `return` or as the body of an arrow function.
+fn is_returned_jsx(ctx: &TraverseCtx<'_, ()>) -> bool {
+    let mut ancestors = ctx.ancestors();
+    while let Some(ancestor) = ancestors.next() {
+        match ancestor {
+            Ancestor::ParenthesizedExpressionExpression(_)
+            | Ancestor::ConditionalExpressionConsequent(_)
+            | Ancestor::ConditionalExpressionAlternate(_)
+            | Ancestor::LogicalExpressionRight(_) => {}
+            Ancestor::ReturnStatementArgument(_) => return true,
+            Ancestor::ExpressionStatementExpression(_) => {
+                return matches!(ancestors.next(), Some(Ancestor::FunctionBodyStatements(_)))
+                    && matches!(ancestors.next(),
+                        Some(Ancestor::ArrowFunctionExpressionBody(arrow)) if *arrow.expression());
+            }
+            _ => return false,
+        }
+    }
+    false
+}
+
+/// Whether an expression evaluates to the same value on every render: literals, and objects,
+/// arrays and `_jsxSorted` calls made of them or of `hoisted` constants.
+fn is_static_expression(
+    expr: &Expression<'_>,
+    hoisted: &HashSet<SymbolId>,
+    scoping: &Scoping,
+) -> bool {
+    let is_static = |expr: &Expression<'_>| is_static_expression(expr, hoisted, scoping);
+    match expr {
+        Expression::StringLiteral(_)
+        | Expression::NumericLiteral(_)
+        | Expression::BooleanLiteral(_)
+        | Expression::NullLiteral(_)
+        | Expression::BigIntLiteral(_) => true,
+        Expression::TemplateLiteral(template) => template.expressions.is_empty(),
+        Expression::Identifier(id) => id
+            .reference_id
+            .get()
+            .and_then(|reference_id| scoping.get_reference(reference_id).symbol_id())
+            .is_some_and(|symbol_id| hoisted.contains(&symbol_id)),
+        Expression::ObjectExpression(object) => object.properties.iter().all(|prop| {
+            matches!(prop, ObjectPropertyKind::ObjectProperty(prop)
+                if !prop.computed && is_static(&prop.value))
+        }),
+        Expression::ArrayExpression(array) => array
+            .elements
+            .iter()
+            .all(|element| element.as_expression().is_some_and(is_static)),
+        Expression::CallExpression(call) => {
+            call.callee.is_specific_id(JSX_SORTED_NAME)
+                && call
+                    .arguments
+                    .iter()
+                    .all(|arg| arg.as_expression().is_some_and(is_static))
+        }
+        _ => false,
+    }
+}
+
+/// Whether the expression is a `sync$` function serialized into a `_qrlSync` call.
+fn is_sync_qrl(expr: &Expression<'_>) -> bool {
+    matches!(expr, Expression::CallExpression(call) if call.callee.is_specific_id(QRL_SYNC_NAME))
+}
+
+/// Whether loop bindings can be appended to the parameters of an event handler, which the runtime
+/// calls with the event and the element first.
+fn accepts_loop_params(function: &Expression<'_>) -> bool {
+    let params = match function {
+        Expression::ArrowFunctionExpression(arrow) => &arrow.params,
+        Expression::FunctionExpression(function) => &function.params,
+        _ => return false,
+    };
+    params.rest.is_none() && params.items.len() <= 2
+}
+
+/// Appends the loop bindings to the parameters of an event handler, padding the parameters it
+/// does not declare: `() => select(item)` becomes `(_, _1, item) => select(item)`.
+fn add_loop_params<'a>(
+    builder: &AstBuilder<'a>,
+    function: &mut Expression<'a>,
+    loop_params: &[SymbolId],
+    scoping: &Scoping,
+) {
+    let params = match function {
+        Expression::ArrowFunctionExpression(arrow) => &mut arrow.params,
+        Expression::FunctionExpression(function) => &mut function.params,
+        _ => return,
+    };
+    let names: Vec<&str> = loop_params
+        .iter()
+        .map(|symbol_id| scoping.symbol_name(*symbol_id))
+        .collect();
+    let declared: Vec<String> = params
+        .items
+        .iter()
+        .filter_map(|param| param.pattern.get_identifier_name())
+        .map(|name| name.to_string())
+        .collect();
+    let padding: Vec<String> = (0..)
+        .map(|n| {
+            if n == 0 {
+                "_".to_string()
+            } else {
+                format!("_{n}")
+            }
+        })
+        .filter(|name| !names.contains(&name.as_str()) && !declared.contains(name))
+        .take(2 - params.items.len())
+        .collect();
+    let param = |name: &str| {
+        builder.formal_parameter(
+            SPAN,
+            builder.vec(),
+            builder.binding_pattern(
+                builder.binding_pattern_kind_binding_identifier(SPAN, builder.atom(name)),
+                None::<OxcBox<TSTypeAnnotation<'a>>>,
+                false,
+            ),
+            None,
+            false,
+            false,
+        )
+    };
+    params.items.extend(padding.iter().map(|name| param(name)));
+    params.items.extend(names.into_iter().map(param));
+}
+
+/// Whether the attribute is a `$` prop whose value is a function to extract into a segment.
+fn has_qrl_prop_value(node: &JSXAttribute<'_>) -> bool {
+    node.name.is_qrl_prop()
+        && matches!(&node.value, Some(JSXAttributeValue::ExpressionContainer(container))
+            if matches!(container.expression, JSXExpression::ArrowFunctionExpression(_) | JSXExpression::FunctionExpression(_)))
+}
+
+fn string_key<'a>(builder: &AstBuilder<'a>, span: Span, name: &str) -> PropertyKey<'a> {
+    PropertyKey::StringLiteral(builder.alloc_string_literal(span, builder.atom(name), None))
+}
+
+/// Creates `inlinedQrl(handler, "handler", [signal])` for a two-way binding, or
+/// `inlinedQrlDEV(handler, "handler", { file, lo, hi, displayName }, [signal])` in dev mode.
+fn inlined_handler<'a>(
+    builder: &AstBuilder<'a>,
+    handler: &'static str,
+    signal: &Expression<'a>,
+    dev: Option<&QrlDev>,
+) -> Expression<'a> {
+    let captures = builder.expression_array(
+        SPAN,
+        builder.vec1(signal.clone_in(builder.allocator).into()),
+    );
+    let mut arguments = builder.vec_from_array([
+        builder.expression_identifier(SPAN, handler).into(),
+        builder
+            .expression_string_literal(SPAN, handler, None)
+            .into(),
+    ]);
+    let callee = match dev {
+        Some(dev) => {
+            arguments.push(dev.to_expression(builder).into());
+            INLINED_QRL_DEV
+        }
+        None => INLINED_QRL,
+    };
+    arguments.push(captures.into());
+    builder.expression_call(
+        SPAN,
+        builder.expression_identifier(SPAN, callee),
+        None::<OxcBox<TSTypeParameterInstantiation<'a>>>,
+        arguments,
+        false,
+    )
+}
+
+fn find_prop_value<'a, 'p>(
+    props: &'p mut OxcVec<'a, ObjectPropertyKind<'a>>,
+    name: &str,
+) -> Option<&'p mut Expression<'a>> {
+    props.iter_mut().find_map(|prop| match prop {
+        ObjectPropertyKind::ObjectProperty(prop)
+            if matches!(&prop.key, PropertyKey::StringLiteral(key) if key.value == name) =>
+        {
+            Some(&mut prop.value)
+        }
+        _ => None,
+    })
+}
+
+/// Combines the handlers of an event that is listened to more than once into a single array.
+fn merge_handlers<'a>(
+    builder: &AstBuilder<'a>,
+    handlers: &mut Expression<'a>,
+    handler: Expression<'a>,
+) {
+    if !matches!(handlers, Expression::ArrayExpression(_)) {
+        let first = move_expression(builder, handlers);
+        *handlers = builder.expression_array(first.span(), builder.vec1(first.into()));
+    }
+    if let Expression::ArrayExpression(array) = handlers {
+        match handler {
+            Expression::ArrayExpression(mut more) => array.elements.append(&mut more.elements),
+            handler => array.elements.push(handler.into()),
+        }
+    }
+}
+
+/// Whether the expression only reads a binding or its properties, e.g. `props` or `store.attrs`.
+fn is_reference(expr: &Expression<'_>) -> bool {
+    match expr {
+        Expression::Identifier(_) => true,
+        Expression::StaticMemberExpression(member) => is_reference(&member.object),
+        _ => false,
+    }
+}
+
+/// The key of an element that spreads `spread` after its key was set to `key`:
+/// `spread?.key ?? key`.
+fn spread_key<'a>(
+    builder: &AstBuilder<'a>,
+    spread: Expression<'a>,
+    key: Expression<'a>,
+) -> Expression<'a> {
+    let spread_key =
+        builder.member_expression_static(SPAN, spread, builder.identifier_name(SPAN, "key"), true);
+    let spread_key = builder.expression_chain(SPAN, ChainElement::from(spread_key));
+    builder.expression_logical(SPAN, spread_key, LogicalOperator::Coalesce, key)
+}
+
+/// The children of an element whose content is rendered as a single string, such as `title` or
+/// `textarea`. Text is joined into one string literal, a lone expression is passed as it is, and
+/// text mixed with expressions becomes a template literal, rather than an array of text nodes.
+fn text_only_children<'a>(
+    builder: &AstBuilder<'a>,
+    span: Span,
+    mut children: OxcVec<'a, ArrayExpressionElement<'a>>,
+) -> Expression<'a> {
+    if children.is_empty() || children.iter().any(|child| !child.is_expression()) {
+        return builder.expression_array(span, children);
+    }
+    if children.len() == 1 && !matches!(children[0], ArrayExpressionElement::StringLiteral(_)) {
+        return children.remove(0).into_expression();
+    }
+
+    let mut quasis = builder.vec();
+    let mut expressions = builder.vec();
+    let mut text = String::new();
+    for child in children {
+        match child.into_expression() {
+            Expression::StringLiteral(literal) => text.push_str(&literal.value),
+            expr => {
+                quasis.push(template_element(builder, &std::mem::take(&mut text), false));
+                expressions.push(expr);
+            }
+        }
+    }
+    if expressions.is_empty() {
+        return builder.expression_string_literal(span, builder.atom(&text), None);
+    }
+    quasis.push(template_element(builder, &text, true));
+    builder.expression_template_literal(span, quasis, expressions)
+}
+
+fn template_element<'a>(builder: &AstBuilder<'a>, text: &str, tail: bool) -> TemplateElement<'a> {
+    let raw = text
+        .replace('\\', "\\\\")
+        .replace('`', "\\`")
+        .replace("${", "\\${");
+    let value = TemplateElementValue {
+        raw: builder.atom(&raw),
+        cooked: Some(builder.atom(text)),
+    };
+    builder.template_element(SPAN, value, tail)
+}
+
 fn is_text_only(node: &str) -> bool {
     matches!(
         node,
@@ -1110,6 +2859,18 @@ pub struct TransformOptions {
     pub target: Target,
     pub transpile_ts: bool,
     pub transpile_jsx: bool,
+    /// Only analyze the source: diagnostics and segment metadata are produced, but no code.
+    pub check_only: bool,
+    /// The path of the module as reported to dev tools, e.g. in the source info of JSX elements.
+    pub dev_path: Option<String>,
+    /// In dev mode, adds a `data-qwik-inspector` attribute with the source location to every
+    /// intrinsic element, so that dev tools can open it in an editor.
+    pub jsx_inspector: bool,
+    /// Segments whose marker name starts with any of these prefixes, e.g. `server`, are not
+    /// emitted; they are replaced with a `_noopQrl`.
+    pub strip_ctx_name: Vec<String>,
+    /// Replaces the segments of event handler props with a `_noopQrl`.
+    pub strip_event_handlers: bool,
 }
 
 impl TransformOptions {
@@ -1122,6 +2883,11 @@ impl TransformOptions {
         self.transpile_jsx = transpile_jsx;
         self
     }
+
+    pub fn with_check_only(mut self, check_only: bool) -> Self {
+        self.check_only = check_only;
+        self
+    }
 }
 
 impl Default for TransformOptions {
@@ -1131,6 +2897,11 @@ impl Default for TransformOptions {
             target: Target::Dev,
             transpile_ts: false,
             transpile_jsx: false,
+            check_only: false,
+            dev_path: None,
+            jsx_inspector: false,
+            strip_ctx_name: Vec::new(),
+            strip_event_handlers: false,
         }
     }
 }
@@ -1141,10 +2912,15 @@ pub fn transform(script_source: Source, options: TransformOptions) -> Result<Opt
     let source_info = script_source.source_info();
     let source_type = script_source.source_info().try_into()?;
 
-    let mut errors = Vec::new();
-
     let parse_return = Parser::new(&allocator, source_text, source_type).parse();
-    errors.extend(parse_return.errors);
+    if parse_return.panicked || !parse_return.errors.is_empty() {
+        let errors = parse_return
+            .errors
+            .iter()
+            .map(ProcessingFailure::parse)
+            .collect();
+        return Ok(OptimizationResult::new(None, errors));
+    }
 
     let mut program = parse_return.program;
 
@@ -1174,13 +2950,26 @@ pub fn transform(script_source: Source, options: TransformOptions) -> Result<Opt
         .with_cfg(true) // Build a Control Flow Graph
         .build(&program);
 
-    let mut transform = TransformGenerator::new(source_info, options, None, &allocator);
+    let mut errors: Vec<ProcessingFailure> = semantic_errors
+        .iter()
+        .map(ProcessingFailure::semantic)
+        .collect();
+
+    let mut transform =
+        TransformGenerator::new(source_info, source_text, options, None, &allocator);
 
     // let (symbols, scopes) = semantic.into_symbol_table_and_scope_tree();
     let scoping = semantic.into_scoping();
 
     traverse_mut(&mut transform, &allocator, &mut program, scoping, ());
 
-    let TransformGenerator { app, errors, .. } = transform;
-    Ok(OptimizationResult::new(app, errors))
+    let TransformGenerator {
+        mut app,
+        errors: transform_errors,
+        options,
+        ..
+    } = transform;
+    errors.extend(transform_errors);
+
+    Ok(OptimizationResult::new(Some(app), errors))
 }